@@ -1,107 +1,541 @@
-use std::borrow::Cow;
-use std::str::FromStr;
-
 use proc_macro::Span;
 use proc_macro2::TokenStream as TokenStream2;
 
-#[proc_macro_derive(Init)]
-pub fn derive_init(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+// Fix Self usages to be the correct name
+struct FixSelfVisitor {
+    replace_self_with: syn::Type,
+}
 
-    let syn::Data::Struct(data_struct) = input.data else {
-        return proc_macro::TokenStream::from_str(r#"compile_error!("TODO");"#).unwrap();
-    };
+impl syn::visit_mut::VisitMut for FixSelfVisitor {
+    fn visit_type_mut(&mut self, node: &mut syn::Type) {
+        if let syn::Type::Path(i) = node
+            && i.qself.is_none()
+            && let Some(first) = i.path.segments.first()
+            && first.ident == "Self"
+        {
+            if i.path.segments.len() > 1 {
+                // Replace first path segment with qualified self if this is a path
+                let new_segments = i.path.segments.iter().skip(1).cloned().collect();
+                i.path.leading_colon = Some(Default::default());
+                i.qself = Some(syn::QSelf {
+                    lt_token: Default::default(),
+                    ty: Box::new(self.replace_self_with.clone()),
+                    position: 0,
+                    as_token: None,
+                    gt_token: Default::default(),
+                });
+                i.path.segments = new_segments;
+            } else {
+                // Else just replace the whole type
+                *node = self.replace_self_with.clone();
+            }
+        }
 
-    let field_count = data_struct.fields.len();
+        syn::visit_mut::visit_type_mut(self, node);
+    }
+}
 
-    let initailizer_name: syn::Ident =
-        syn::Ident::new(&format!("{}Init", input.ident), Span::call_site().into());
-    let generic_names: Vec<syn::Ident> = (0..field_count)
-        .map(|n| syn::Ident::new(&format!("_Field{n}"), Span::call_site().into()))
-        .collect();
-    let generics: Vec<syn::GenericParam> = generic_names
-        .iter()
-        .map(|name| syn::GenericParam::Type(syn::TypeParam::from(name.clone())))
-        .collect();
+/// Options from `#[init(...)]` attributes on the derived type.
+#[derive(Default)]
+struct InitOptions {
+    /// `name = Ident`: the name of the initializer struct (or module, for enums).
+    name: Option<syn::Ident>,
+    /// `vis = Visibility`: the visibility of the initializer struct (or module, for enums).
+    vis: Option<syn::Visibility>,
+    /// `builder`: generate a typestate builder, starting from `Type::init()`.
+    builder: bool,
+    /// `pinned_drop`: implement `Drop` by calling `PinnedDrop::drop` (only for `#[derive(PinInit)]`).
+    pinned_drop: bool,
+}
 
-    //TODO: use quote instead
-
-    let initializer_struct = syn::ItemStruct {
-        attrs: vec![],
-        vis: input.vis,
-        struct_token: data_struct.struct_token,
-        ident: initailizer_name.clone(),
-        generics: syn::Generics {
-            lt_token: None,
-            params: syn::punctuated::Punctuated::from_iter(generics.iter().cloned()),
-            gt_token: None,
-            where_clause: None,
-        },
-        fields: syn::Fields::Unnamed(syn::FieldsUnnamed {
-            paren_token: Default::default(),
-            unnamed: syn::punctuated::Punctuated::from_iter(generic_names.iter().map(|tn| {
-                syn::Field {
-                    attrs: vec![],
-                    vis: syn::Visibility::Public(Default::default()),
-                    mutability: syn::FieldMutability::None,
-                    ident: None,
-                    colon_token: None,
-                    ty: syn::Type::Path(syn::TypePath {
-                        qself: None,
-                        path: syn::Path {
-                            leading_colon: None,
-                            segments: syn::punctuated::Punctuated::from_iter([
-                                syn::PathSegment::from(tn.clone()),
-                            ]),
-                        },
-                    }),
+impl InitOptions {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("init")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    options.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("vis") {
+                    options.vis = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("builder") {
+                    options.builder = true;
+                } else if meta.path.is_ident("pinned_drop") {
+                    options.pinned_drop = true;
+                } else {
+                    return Err(meta.error("unknown `init` option"));
                 }
-            })),
-        }),
-        semi_token: Default::default(),
-    };
-
-    let struct_name = input.ident;
-    let (_, struct_generics, where_clause) = input.generics.split_for_impl();
-    let impl_generics = &input.generics.params;
-    let replace_self_with: syn::Type = syn::parse_quote!( #struct_name #struct_generics );
-    // Fix Self usages to be the correct name
-    struct FixSelfVisitor {
-        replace_self_with: syn::Type,
+                Ok(())
+            })?;
+        }
+        Ok(options)
     }
+}
 
-    impl syn::visit_mut::VisitMut for FixSelfVisitor {
-        fn visit_type_mut(&mut self, node: &mut syn::Type) {
-            if let syn::Type::Path(i) = node
-                && i.qself.is_none()
-                && let Some(first) = i.path.segments.first()
-                && first.ident == "Self"
-            {
-                if i.path.segments.len() > 1 {
-                    // Replace first path segment with qualified self if this is a path
-                    let new_segments = i.path.segments.iter().skip(1).cloned().collect();
-                    i.path.leading_colon = Some(Default::default());
-                    i.qself = Some(syn::QSelf {
-                        lt_token: Default::default(),
-                        ty: Box::new(self.replace_self_with.clone()),
-                        position: 0,
-                        as_token: None,
-                        gt_token: Default::default(),
-                    });
-                    i.path.segments = new_segments;
+/// How a field is initialized, from an `#[init(...)]` attribute on the field.
+enum FieldInit {
+    /// By an initializer given as a field of the generated initializer struct.
+    Param(ExtraRoute),
+    /// `default`: with `Default::default()`.
+    Default,
+    /// `zeroed`, or `unsafe(zeroed)`: with zero bytes, checked by `bytemuck::Zeroable` unless unsafe.
+    Zeroed { unchecked: bool },
+    /// `value = Expr`: with a fixed value.
+    Value(syn::Expr),
+    /// `uninit`: left uninitialized, for `MaybeUninit` fields.
+    Uninit,
+}
+
+/// Which part of `extra` a parameter field's initializer gets.
+enum ExtraRoute {
+    /// A clone of `extra`, shared with the other fields without an `extra` option, unless any
+    /// field has `extra` or `extra = n`, in which case `()`.
+    Shared,
+    /// `extra`: `extra` itself, moved.
+    Moved(proc_macro2::Span),
+    /// `extra = n`: element `n` of a tuple `extra`.
+    Index(usize, proc_macro2::Span),
+    /// `no_extra`: `()`.
+    None,
+}
+
+impl FieldInit {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut field_init = Self::Param(ExtraRoute::Shared);
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("init")) {
+            attr.parse_nested_meta(|meta| {
+                if !matches!(field_init, Self::Param(ExtraRoute::Shared)) {
+                    return Err(meta.error("a field can only have one `init` option"));
+                }
+                if meta.path.is_ident("default") {
+                    field_init = Self::Default;
+                } else if meta.path.is_ident("zeroed") {
+                    field_init = Self::Zeroed { unchecked: false };
+                } else if meta.path.is_ident("value") {
+                    field_init = Self::Value(meta.value()?.parse()?);
+                } else if meta.path.is_ident("uninit") {
+                    field_init = Self::Uninit;
+                } else if meta.path.is_ident("unsafe") {
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("zeroed") {
+                            field_init = Self::Zeroed { unchecked: true };
+                            Ok(())
+                        } else {
+                            Err(meta.error("unknown unsafe `init` option"))
+                        }
+                    })?;
+                } else if meta.path.is_ident("extra") {
+                    let span = syn::spanned::Spanned::span(&meta.path);
+                    field_init = if meta.input.peek(syn::Token![=]) {
+                        let index: syn::LitInt = meta.value()?.parse()?;
+                        Self::Param(ExtraRoute::Index(index.base10_parse()?, span))
+                    } else {
+                        Self::Param(ExtraRoute::Moved(span))
+                    };
+                } else if meta.path.is_ident("no_extra") {
+                    field_init = Self::Param(ExtraRoute::None);
                 } else {
-                    // Else just replace the whole type
-                    *node = self.replace_self_with.clone();
+                    return Err(meta.error("unknown `init` option"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(field_init)
+    }
+
+    fn parse_all(fields: &syn::Fields) -> syn::Result<Vec<Self>> {
+        fields.iter().map(|f| Self::parse(&f.attrs)).collect()
+    }
+}
+
+/// How the generated impls take `Extra`, and pass it to each parameter field.
+struct ExtraRouting {
+    /// The generic parameters of the impls for `Extra`, each followed by a comma.
+    generics: TokenStream2,
+    /// The `Extra` type of the impls.
+    ty: TokenStream2,
+    /// The statements of `PinInit::init` that split `extra` for the fields.
+    split: TokenStream2,
+    /// For each field, the `Extra` type of its initializer, if it is a parameter field.
+    field_tys: Vec<TokenStream2>,
+    /// For each field, the expression for its extra, if it is a parameter field.
+    field_extras: Vec<TokenStream2>,
+}
+
+impl ExtraRouting {
+    fn new(field_inits: &[FieldInit]) -> syn::Result<Self> {
+        let routes: Vec<_> = field_inits
+            .iter()
+            .map(|field_init| match field_init {
+                FieldInit::Param(route) => Some(route),
+                _ => None,
+            })
+            .collect();
+        let mut moved = None;
+        let mut indices: Vec<Option<usize>> = vec![None; field_inits.len()];
+        for (n, route) in routes.iter().enumerate() {
+            match route {
+                Some(ExtraRoute::Moved(span)) if moved.is_some() => {
+                    return Err(syn::Error::new(
+                        *span,
+                        "only one field can take `extra`; use `extra = n` to split a tuple `extra` between fields",
+                    ));
+                }
+                Some(ExtraRoute::Moved(span)) if indices.iter().any(Option::is_some) => {
+                    return Err(syn::Error::new(
+                        *span,
+                        "`extra` cannot be combined with `extra = n`",
+                    ));
+                }
+                Some(ExtraRoute::Moved(_)) => moved = Some(n),
+                Some(ExtraRoute::Index(_, span)) if moved.is_some() => {
+                    return Err(syn::Error::new(
+                        *span,
+                        "`extra = n` cannot be combined with `extra`",
+                    ));
+                }
+                Some(ExtraRoute::Index(index, span)) if indices.contains(&Some(*index)) => {
+                    return Err(syn::Error::new(
+                        *span,
+                        format!("element {index} of `extra` is already taken by another field"),
+                    ));
                 }
+                Some(ExtraRoute::Index(index, _)) => indices[n] = Some(*index),
+                _ => {}
+            }
+        }
+
+        let unit = || quote::quote!(());
+        let mut field_tys: Vec<_> = routes.iter().map(|_| unit()).collect();
+        let mut field_extras: Vec<_> = routes.iter().map(|_| unit()).collect();
+
+        if let Some(n) = moved {
+            field_tys[n] = quote::quote!(Extra);
+            field_extras[n] = quote::quote!(__extra);
+            return Ok(Self {
+                generics: quote::quote!(Extra,),
+                ty: quote::quote!(Extra),
+                split: TokenStream2::new(),
+                field_tys,
+                field_extras,
+            });
+        }
+
+        if let Some(len) = indices.iter().flatten().max().map(|max| max + 1) {
+            let element_tys: Vec<_> = (0..len)
+                .map(|n| syn::Ident::new(&format!("__Extra{n}"), Span::call_site().into()))
+                .collect();
+            let elements: Vec<_> = (0..len)
+                .map(|n| syn::Ident::new(&format!("__extra{n}"), Span::call_site().into()))
+                .collect();
+            for (n, index) in indices.iter().enumerate() {
+                if let Some(index) = *index {
+                    let (ty, element) = (&element_tys[index], &elements[index]);
+                    field_tys[n] = quote::quote!(#ty);
+                    field_extras[n] = quote::quote!(#element);
+                }
+            }
+            return Ok(Self {
+                generics: quote::quote!(#( #element_tys, )*),
+                ty: quote::quote!(( #( #element_tys, )* )),
+                split: quote::quote!(let ( #( #elements, )* ) = __extra;),
+                field_tys,
+                field_extras,
+            });
+        }
+
+        // Every sharing field but the last gets a clone of `extra`.
+        let sharing: Vec<_> = (0..routes.len())
+            .filter(|&n| matches!(routes[n], Some(ExtraRoute::Shared)))
+            .collect();
+        for &n in &sharing {
+            field_tys[n] = quote::quote!(Extra);
+            field_extras[n] = if Some(&n) == sharing.last() {
+                quote::quote!(__extra)
+            } else {
+                quote::quote!(__extra.clone())
+            };
+        }
+        let (generics, ty) = match sharing.len() {
+            0 => (TokenStream2::new(), unit()),
+            1 => (quote::quote!(Extra,), quote::quote!(Extra)),
+            _ => (quote::quote!(Extra: Clone,), quote::quote!(Extra)),
+        };
+        Ok(Self {
+            generics,
+            ty,
+            split: TokenStream2::new(),
+            field_tys,
+            field_extras,
+        })
+    }
+
+    /// The `Extra` types of the initializers of the parameter fields.
+    fn param_tys(&self, field_inits: &[FieldInit]) -> Vec<&TokenStream2> {
+        field_inits
+            .iter()
+            .zip(&self.field_tys)
+            .filter(|(field_init, _)| matches!(field_init, FieldInit::Param(_)))
+            .map(|(_, ty)| ty)
+            .collect()
+    }
+}
+
+/// The fields that are initialized by a field of the generated initializer struct.
+fn param_fields(fields: &syn::Fields, field_inits: &[FieldInit]) -> syn::Fields {
+    let mut fields = fields.clone();
+    let filter = |punctuated: &mut syn::punctuated::Punctuated<syn::Field, syn::Token![,]>| {
+        *punctuated = std::mem::take(punctuated)
+            .into_iter()
+            .zip(field_inits)
+            .filter(|(_, field_init)| matches!(field_init, FieldInit::Param(_)))
+            .map(|(field, _)| field)
+            .collect();
+    };
+    match &mut fields {
+        syn::Fields::Named(named) => filter(&mut named.named),
+        syn::Fields::Unnamed(unnamed) => filter(&mut unnamed.unnamed),
+        syn::Fields::Unit => {}
+    }
+    fields
+}
+
+/// The statements of `PinInit::init` that initialize each field in order, through the pointers
+/// `dsts`, and then forget the guards that would drop the initialized fields if a later field
+/// failed.
+///
+/// Parameter fields are initialized by the initializer bound to their name in `bindings`, with
+/// their part of `extra`.
+fn field_init_statements(
+    field_inits: &[FieldInit],
+    field_tys: &[syn::Type],
+    extra: &ExtraRouting,
+    bindings: &[syn::Ident],
+    dsts: &[TokenStream2],
+) -> TokenStream2 {
+    let statements = field_inits.iter().enumerate().map(|(n, field_init)| {
+        let ty = &field_tys[n];
+        // Point errors from unsatisfied bounds at the field's type.
+        use syn::spanned::Spanned;
+        let binding = &bindings[n];
+        match field_init {
+            FieldInit::Param(_) => {
+                let field_extra = &extra.field_extras[n];
+                quote::quote!(unsafe { #binding.init(__dst, #field_extra) }?;)
+            }
+            // Values are evaluated outside of `unsafe`, since they are user code.
+            FieldInit::Default => quote::quote! {
+                let __value: #ty = ::core::default::Default::default();
+                unsafe { __dst.write(__value) };
+            },
+            FieldInit::Value(value) => quote::quote! {
+                let __value: #ty = #value;
+                unsafe { __dst.write(__value) };
+            },
+            FieldInit::Zeroed { unchecked: false } => quote::quote_spanned! {ty.span()=>
+                unsafe { ::in_place_init::PinInit::<#ty, __Error>::init(::in_place_init::Zeroed::<#ty>::new_zeroable(), __dst, ())?; }
+            },
+            FieldInit::Zeroed { unchecked: true } => quote::quote_spanned! {ty.span()=>
+                unsafe { ::in_place_init::PinInit::<#ty, __Error>::init(::in_place_init::Zeroed::<#ty>::new_unchecked(()), __dst, ())?; }
+            },
+            FieldInit::Uninit => quote::quote_spanned! {ty.span()=>
+                unsafe { ::in_place_init::PinInit::<#ty, __Error>::init(::in_place_init::Uninit::new(), __dst, ())?; }
+            },
+        }
+    });
+    let split = &extra.split;
+    quote::quote! {
+        #split
+        #(
+            let #bindings = {
+                let __dst = unsafe { #dsts };
+                #statements
+                unsafe { ::in_place_init::noop_allocator::owning_ref::from_raw(__dst) }
+            };
+        )*
+        ::core::mem::forget((#(#bindings,)*));
+    }
+}
+
+/// The names bound to each field's initializer (for parameter fields, which are the names of
+/// their generic parameters) or to the guard of each initialized field.
+fn field_bindings(field_inits: &[FieldInit]) -> (Vec<syn::Ident>, Vec<syn::Ident>) {
+    let mut generic_names = vec![];
+    let bindings = field_inits
+        .iter()
+        .enumerate()
+        .map(|(n, field_init)| {
+            if let FieldInit::Param(_) = field_init {
+                let name = syn::Ident::new(
+                    &format!("_Field{}", generic_names.len()),
+                    Span::call_site().into(),
+                );
+                generic_names.push(name.clone());
+                name
+            } else {
+                syn::Ident::new(&format!("_Fixed{n}"), Span::call_site().into())
             }
+        })
+        .collect();
+    (generic_names, bindings)
+}
 
-            syn::visit_mut::visit_type_mut(self, node);
+/// The body of an initializer struct with one public field of type `generic_names[n]` for each
+/// of `fields`, and the pattern (without the path) that destructures it into bindings named
+/// `generic_names`.
+fn initializer_fields(
+    fields: &syn::Fields,
+    generic_names: &[syn::Ident],
+) -> (TokenStream2, TokenStream2) {
+    match fields {
+        syn::Fields::Named(named) => {
+            let field_names: Vec<_> = named.named.iter().map(|f| &f.ident).collect();
+            (
+                quote::quote!({ #( pub #field_names: #generic_names, )* }),
+                quote::quote!({ #( #field_names: #generic_names, )* }),
+            )
         }
+        syn::Fields::Unnamed(_) => (
+            quote::quote!(( #( pub #generic_names, )* );),
+            quote::quote!(( #( #generic_names, )* )),
+        ),
+        syn::Fields::Unit => (quote::quote!(;), quote::quote!()),
+    }
+}
+
+/// Derive an initializer for a struct, or one initializer per variant for an enum.
+///
+/// For a struct `Foo`, this generates a struct `FooInit` with one generic field per field of
+/// `Foo` (named like `Foo`'s fields, or a tuple struct if `Foo` is one), which implements
+/// `PinInit<Foo>` (and `Init<Foo>`) when each of its fields is an initializer for the
+/// corresponding field of `Foo`.
+///
+/// For an enum `Foo`, which must be `#[repr(C)]` or have a primitive representation, this
+/// generates a module `FooInit` with one such struct per variant, e.g. `FooInit::Variant`.
+///
+/// Options can be given with `#[init(...)]`:
+///
+/// * `name = Ident`: the name of the generated struct (or module).
+/// * `vis = Visibility`: the visibility of the generated struct (or module), instead of that of `Foo`.
+/// * `builder`: also generate `Foo::init()`, which returns a `FooInit` with every field `Unset`,
+///   and a setter method for each field, e.g. `Foo::init().x(a).y(b)`.
+///
+/// Fields can be initialized without a field of the generated struct with `#[init(...)]`:
+///
+/// * `default`: with `Default::default()`.
+/// * `zeroed`: with zero bytes, which requires the `bytemuck` feature and `Field: Zeroable`.
+///   `unsafe(zeroed)` skips that check, so zero bytes must be a valid `Field`.
+/// * `value = Expr`: with the value of `Expr`, which is evaluated during initialization.
+/// * `uninit`: left uninitialized, which requires `Field` to be a `MaybeUninit`.
+///
+/// By default, the initializer of each field gets a clone of the `Extra` argument (so `Extra`
+/// must be `Clone` if there are several fields). That can be changed with `#[init(...)]`:
+///
+/// * `extra`: this field gets the `Extra` argument itself, and fields without these options get `()`.
+/// * `extra = n`: `Extra` is a tuple, and this field gets its element `n`. Fields without these
+///   options get `()`.
+/// * `no_extra`: this field gets `()`.
+#[proc_macro_derive(Init, attributes(init))]
+pub fn derive_init(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive(input, false).into()
+}
+
+/// Derive a pinned initializer for a struct, with structural pinning for fields marked `#[pin]`.
+///
+/// Like `#[derive(Init)]`, this generates a struct `FooInit`, which implements `PinInit<Foo>`
+/// when each field marked `#[pin]` gets a `PinInit` initializer, and each other field gets an
+/// `Init` initializer (since those fields can be moved out of a `Pin<&mut Foo>`).
+///
+/// It also generates:
+///
+/// * `Foo::project(self: Pin<&mut Self>) -> FooProj<'_>` and
+///   `Foo::project_ref(self: Pin<&Self>) -> FooProjRef<'_>`, where `FooProj` has a
+///   `Pin<&mut Field>` for each pinned field, and a `&mut Field` for each other field.
+/// * An `Unpin` impl for `Foo` if all pinned fields are `Unpin`.
+///
+/// `Foo` must not implement `Drop`, since that would allow moving pinned fields. Instead, add
+/// `#[init(pinned_drop)]` and implement `PinnedDrop` for `Foo`.
+///
+/// The options of `#[derive(Init)]` are also supported, except for enums.
+#[proc_macro_derive(PinInit, attributes(init, pin))]
+pub fn derive_pin_init(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive(input, true).into()
+}
+
+/// The shared implementation of `#[derive(Init)]` and (with `pin`) `#[derive(PinInit)]`.
+fn derive(input: syn::DeriveInput, pin: bool) -> TokenStream2 {
+    let options = match InitOptions::parse(&input.attrs) {
+        Ok(options) => options,
+        Err(err) => return err.to_compile_error(),
+    };
+    if options.pinned_drop && !pin {
+        return syn::Error::new_spanned(
+            &input.ident,
+            "`#[init(pinned_drop)]` requires `#[derive(PinInit)]`",
+        )
+        .to_compile_error();
     }
 
+    let data_struct = match input.data {
+        syn::Data::Struct(data_struct) => data_struct,
+        syn::Data::Enum(_) if pin => {
+            return syn::Error::new_spanned(
+                &input.ident,
+                "`#[derive(PinInit)]` is not supported for enums",
+            )
+            .to_compile_error();
+        }
+        syn::Data::Enum(data_enum) => {
+            return derive_enum(
+                &input.attrs,
+                options,
+                &input.vis,
+                &input.ident,
+                &input.generics,
+                data_enum,
+            );
+        }
+        syn::Data::Union(_) => {
+            return syn::Error::new_spanned(
+                &input.ident,
+                "deriving initializers is not supported for unions",
+            )
+            .to_compile_error();
+        }
+    };
+
+    let initializer_name = options.name.clone().unwrap_or_else(|| {
+        syn::Ident::new(&format!("{}Init", input.ident), Span::call_site().into())
+    });
+    let vis = options.vis.clone().unwrap_or(input.vis);
+
+    // Unit structs get an initializer with no fields, like tuple structs.
+    let fields = match data_struct.fields {
+        syn::Fields::Unit => syn::Fields::Unnamed(syn::parse_quote!(())),
+        fields => fields,
+    };
+    let field_inits = match FieldInit::parse_all(&fields) {
+        Ok(field_inits) => field_inits,
+        Err(err) => return err.to_compile_error(),
+    };
+    let param_fields = param_fields(&fields, &field_inits);
+    let (generic_names, bindings) = field_bindings(&field_inits);
+    let (initializer_body, initializer_pattern) = initializer_fields(&param_fields, &generic_names);
+
+    let struct_name = input.ident;
+    let (struct_impl_generics, struct_generics, where_clause) = input.generics.split_for_impl();
+    // Lifetimes must be declared before the initializer's type parameters.
+    let lifetime_params: Vec<_> = input.generics.lifetimes().collect();
+    let impl_params: Vec<_> = input
+        .generics
+        .params
+        .iter()
+        .filter(|param| !matches!(param, syn::GenericParam::Lifetime(_)))
+        .collect();
+    let replace_self_with: syn::Type = syn::parse_quote!( #struct_name #struct_generics );
     let mut visitor = FixSelfVisitor { replace_self_with };
-    let field_tys: Vec<_> = data_struct
-        .fields
+    let field_tys: Vec<_> = fields
         .iter()
         .map(|f| f.ty.clone())
         .map(|mut ty| {
@@ -111,61 +545,613 @@ pub fn derive_init(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         })
         .collect();
 
-    let tail_ty: Vec<_> = field_tys.last().into_iter().cloned().collect();
-    let tail_generic: Vec<_> = generic_names.last().into_iter().cloned().collect();
+    // Only an unsized tail field has metadata, and it can only be initialized by a parameter.
+    let (tail_ty, tail_generic): (Vec<_>, Vec<_>) = match field_inits.last() {
+        Some(FieldInit::Param(_)) => (
+            field_tys.last().into_iter().cloned().collect(),
+            generic_names.last().into_iter().cloned().collect(),
+        ),
+        _ => (vec![], vec![]),
+    };
+
+    let extra = match ExtraRouting::new(&field_inits) {
+        Ok(extra) => extra,
+        Err(err) => return err.to_compile_error(),
+    };
+    let (extra_generics, extra_type) = (&extra.generics, &extra.ty);
+    let param_extra_tys = extra.param_tys(&field_inits);
+
+    let dsts: Vec<_> = fields
+        .members()
+        .map(|member| quote::quote!(&raw mut (*__dst).#member))
+        .collect();
+    let init_statements = field_init_statements(&field_inits, &field_tys, &extra, &bindings, &dsts);
+
+    let where_predicates: Vec<_> = where_clause
+        .map(|wc| wc.predicates.iter().collect())
+        .unwrap_or_default();
 
-    let (extra_generic, extra_type): (TokenStream2, TokenStream2) = if field_count == 0 {
-        (TokenStream2::new(), "()".parse().unwrap())
-    } else if field_count == 1 {
-        ("Extra,".parse().unwrap(), "Extra".parse().unwrap())
+    let builder = if options.builder {
+        match derive_builder(
+            &param_fields,
+            &vis,
+            &initializer_name,
+            &generic_names,
+            &struct_name,
+            (&struct_impl_generics, &struct_generics, where_clause),
+        ) {
+            Ok(builder) => builder,
+            Err(err) => return err.to_compile_error(),
+        }
     } else {
-        ("Extra: Clone,".parse().unwrap(), "Extra".parse().unwrap())
+        TokenStream2::new()
     };
 
-    let dst_members = data_struct.fields.members();
-
-    let where_clause = match where_clause {
-        Some(wc) if !wc.predicates.is_empty() => {
-            let mut wc = Cow::Borrowed(wc);
-            if !wc.predicates.trailing_punct() {
-                wc.to_mut().predicates.push_punct(Default::default());
+    // With structural pinning, only `#[pin]` fields can be pin-initialized.
+    let pinned: Vec<bool> = fields
+        .iter()
+        .map(|f| pin && f.attrs.iter().any(|attr| attr.path().is_ident("pin")))
+        .collect();
+    let (param_tys, param_bounds): (Vec<_>, Vec<_>) = field_inits
+        .iter()
+        .zip(&pinned)
+        .zip(&field_tys)
+        .filter(|((field_init, _), _)| matches!(field_init, FieldInit::Param(_)))
+        .map(|((_, &pinned), ty)| {
+            if pinned || !pin {
+                (ty, quote::quote!(::in_place_init::PinInit))
+            } else {
+                (ty, quote::quote!(::in_place_init::Init))
             }
-            Box::new(wc) as Box<dyn quote::ToTokens>
+        })
+        .unzip();
+
+    let pin_projection = if pin {
+        match derive_pin_projection(
+            &input.attrs,
+            &options,
+            &fields,
+            &pinned,
+            &field_tys,
+            &vis,
+            &struct_name,
+            &input.generics,
+        ) {
+            Ok(pin_projection) => pin_projection,
+            Err(err) => return err.to_compile_error(),
         }
-        _ => Box::new(TokenStream2::from_str("where").unwrap()),
+    } else {
+        TokenStream2::new()
     };
 
     quote::quote! {
-        #initializer_struct
+        #vis struct #initializer_name< #( #generic_names, )* > #initializer_body
 
-        unsafe impl<#extra_generic __Error, #( #generic_names, )* #impl_generics > ::in_place_init::PinInit<#struct_name #struct_generics, __Error, #extra_type> for #initailizer_name< #(#generic_names,)* >
-            #where_clause
-                #( #generic_names: ::in_place_init::PinInit<#field_tys, __Error, #extra_type>, )*
+        unsafe impl<#( #lifetime_params, )* #extra_generics __Error, #( #generic_names, )* #( #impl_params, )* > ::in_place_init::PinInit<#struct_name #struct_generics, __Error, #extra_type> for #initializer_name< #(#generic_names,)* >
+            where
+                #( #where_predicates, )*
+                #( #generic_names: #param_bounds<#param_tys, __Error, #param_extra_tys>, )*
         {
 
             fn metadata(&self) #( -> <#tail_ty as ::core::ptr::Pointee>::Metadata )* {
-                let Self( #( #generic_names, )* ) = self;
+                let Self #initializer_pattern = self;
                 #( #tail_generic.metadata() )*
             }
 
-            unsafe fn init(self, dst: *mut #struct_name #struct_generics, extra: #extra_type) -> Result<(), __Error> {
-                let Self(#(#generic_names,)*) = self;
-                #(
-                    let #generic_names = {
-                        let dst = &raw mut (*dst).#dst_members;
-                        #generic_names.init(dst, extra.clone())?;
-                        ::in_place_init::noop_allocator::owning_ref::from_raw(dst)
-                    };
-                )*
-                ::core::mem::forget((#(#generic_names,)*));
+            #[deny(unsafe_op_in_unsafe_fn)]
+            unsafe fn init(self, __dst: *mut #struct_name #struct_generics, __extra: #extra_type) -> Result<(), __Error> {
+                let Self #initializer_pattern = self;
+                #init_statements
                 Ok(())
             }
         }
 
-        unsafe impl<#extra_generic __Error, #( #generic_names, )* #impl_generics > ::in_place_init::Init<#struct_name #struct_generics, __Error, #extra_type> for #initailizer_name< #(#generic_names,)* >
+        unsafe impl<#( #lifetime_params, )* #extra_generics __Error, #( #generic_names, )* #( #impl_params, )* > ::in_place_init::Init<#struct_name #struct_generics, __Error, #extra_type> for #initializer_name< #(#generic_names,)* >
             where
-                #( #generic_names: ::in_place_init::Init<#field_tys, __Error, #extra_type>, )*
+                #( #where_predicates, )*
+                #( #generic_names: ::in_place_init::Init<#param_tys, __Error, #param_extra_tys>, )*
         {}
 
-    }.into()
+        #builder
+
+        #pin_projection
+    }
+}
+
+/// Generate `Type::init()`, which returns the initializer with every field [`Unset`], and one
+/// setter method per field that is only available while that field is unset.
+///
+/// [`Unset`]: https://docs.rs/in-place-init/latest/in_place_init/struct.Unset.html
+fn derive_builder(
+    fields: &syn::Fields,
+    vis: &syn::Visibility,
+    initializer_name: &syn::Ident,
+    generic_names: &[syn::Ident],
+    struct_name: &syn::Ident,
+    (impl_generics, struct_generics, where_clause): (
+        &syn::ImplGenerics,
+        &syn::TypeGenerics,
+        Option<&syn::WhereClause>,
+    ),
+) -> syn::Result<TokenStream2> {
+    let syn::Fields::Named(named) = fields else {
+        return Err(syn::Error::new(
+            Span::call_site().into(),
+            "`#[init(builder)]` requires a struct with named fields",
+        ));
+    };
+    let field_names: Vec<_> = named
+        .named
+        .iter()
+        .map(|f| f.ident.as_ref().unwrap())
+        .collect();
+
+    let setters = field_names.iter().enumerate().map(|(n, field_name)| {
+        let other_generics = generic_names
+            .iter()
+            .enumerate()
+            .filter(|&(m, _)| m != n)
+            .map(|(_, name)| name);
+        let unset_generics = generic_names.iter().enumerate().map(|(m, name)| {
+            if m == n {
+                quote::quote!(::in_place_init::Unset)
+            } else {
+                quote::quote!(#name)
+            }
+        });
+        let other_fields: Vec<_> = field_names
+            .iter()
+            .enumerate()
+            .filter(|&(m, _)| m != n)
+            .map(|(_, name)| name)
+            .collect();
+        let generic_name = &generic_names[n];
+        quote::quote! {
+            impl< #( #other_generics, )* > #initializer_name< #( #unset_generics, )* > {
+                #vis fn #field_name<#generic_name>(self, #field_name: #generic_name) -> #initializer_name< #( #generic_names, )* > {
+                    let Self { #field_name: _, #( #other_fields, )* } = self;
+                    #initializer_name { #( #field_names, )* }
+                }
+            }
+        }
+    });
+
+    let all_unset = generic_names
+        .iter()
+        .map(|_| quote::quote!(::in_place_init::Unset));
+    Ok(quote::quote! {
+        impl #impl_generics #struct_name #struct_generics #where_clause {
+            #vis fn init() -> #initializer_name< #( #all_unset, )* > {
+                #initializer_name { #( #field_names: ::in_place_init::Unset, )* }
+            }
+        }
+
+        #( #setters )*
+    })
+}
+
+/// The visibility of an item inside a module generated next to the derived type,
+/// such that it is visible wherever the derived type is.
+fn nested_visibility(vis: &syn::Visibility) -> TokenStream2 {
+    match vis {
+        syn::Visibility::Public(_) => quote::quote!(pub),
+        syn::Visibility::Inherited => quote::quote!(pub(super)),
+        syn::Visibility::Restricted(restricted) => {
+            let path = &restricted.path;
+            match path.segments.first() {
+                _ if path.leading_colon.is_some() => quote::quote!(#vis),
+                Some(first) if first.ident == "crate" => quote::quote!(#vis),
+                Some(first) if first.ident == "self" => {
+                    let rest = path.segments.iter().skip(1);
+                    quote::quote!(pub(in super #(:: #rest)*))
+                }
+                _ => quote::quote!(pub(in super:: #path)),
+            }
+        }
+    }
+}
+
+/// Derive `Init` for an enum with a defined layout, generating a module `{Enum}Init` with one
+/// initializer struct per variant.
+///
+/// The layout of `#[repr(C)]` and primitive-representation enums is specified in terms of
+/// `#[repr(C)]` structs and unions (see RFC 2195), so this defines those as helper types, and
+/// projects the destination pointer through them to write the tag and the fields.
+fn derive_enum(
+    attrs: &[syn::Attribute],
+    options: InitOptions,
+    vis: &syn::Visibility,
+    enum_name: &syn::Ident,
+    generics: &syn::Generics,
+    data_enum: syn::DataEnum,
+) -> TokenStream2 {
+    let mut repr_c = false;
+    let mut repr_int = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                repr_c = true;
+            } else if let Some(ident) = meta.path.get_ident()
+                && matches!(
+                    ident.to_string().as_str(),
+                    "u8" | "u16"
+                        | "u32"
+                        | "u64"
+                        | "u128"
+                        | "usize"
+                        | "i8"
+                        | "i16"
+                        | "i32"
+                        | "i64"
+                        | "i128"
+                        | "isize"
+                )
+            {
+                repr_int = Some(ident.clone());
+            } else if meta.input.peek(syn::token::Paren) {
+                // e.g. `align(N)`, which does not affect where the tag and fields are placed
+                let _: TokenStream2 = meta.input.parse::<proc_macro2::Group>()?.stream();
+            }
+            Ok(())
+        });
+        if let Err(err) = result {
+            return err.to_compile_error();
+        }
+    }
+    if !repr_c && repr_int.is_none() {
+        return syn::Error::new_spanned(
+            enum_name,
+            "deriving `Init` for an enum requires `#[repr(C)]` or a primitive representation like `#[repr(u8)]`",
+        )
+        .to_compile_error();
+    }
+
+    if options.builder {
+        return syn::Error::new_spanned(enum_name, "`#[init(builder)]` is not supported for enums")
+            .to_compile_error();
+    }
+
+    let module_name = options
+        .name
+        .unwrap_or_else(|| syn::Ident::new(&format!("{enum_name}Init"), Span::call_site().into()));
+    let vis = &options.vis.unwrap_or_else(|| vis.clone());
+    let nested_vis = nested_visibility(vis);
+
+    let (impl_generics, enum_generics, where_clause) = generics.split_for_impl();
+    // Lifetimes must be declared before the initializer's type parameters.
+    let lifetime_params: Vec<_> = generics.lifetimes().collect();
+    let impl_params: Vec<_> = generics
+        .params
+        .iter()
+        .filter(|param| !matches!(param, syn::GenericParam::Lifetime(_)))
+        .collect();
+    let where_predicates: Vec<_> = where_clause
+        .map(|wc| wc.predicates.iter().collect())
+        .unwrap_or_default();
+    let replace_self_with: syn::Type = syn::parse_quote!( #enum_name #enum_generics );
+    let mut visitor = FixSelfVisitor { replace_self_with };
+
+    let tag_repr = match &repr_int {
+        Some(int) => quote::quote!(#[repr(#int)]),
+        None => quote::quote!(#[repr(C)]),
+    };
+    let variant_names: Vec<_> = data_enum.variants.iter().map(|v| &v.ident).collect();
+    let discriminants = data_enum.variants.iter().map(|v| {
+        let discriminant = v
+            .discriminant
+            .as_ref()
+            .map(|(eq, expr)| quote::quote!(#eq #expr));
+        quote::quote!(#discriminant)
+    });
+    let variant_reprs: Vec<_> = variant_names
+        .iter()
+        .map(|name| syn::Ident::new(&format!("__{name}Repr"), Span::call_site().into()))
+        .collect();
+
+    // With a primitive representation (and no `C`), each variant is laid out as a `#[repr(C)]`
+    // struct starting with the tag. Otherwise, the enum is a `#[repr(C)]` struct of the tag and
+    // a union of `#[repr(C)]` structs of each variant's fields.
+    let tag_in_variant = !repr_c;
+    let tag_field = tag_in_variant.then(|| quote::quote!(__Tag,));
+
+    let mut helper_types = vec![quote::quote! {
+        #[allow(dead_code)]
+        #tag_repr
+        enum __Tag {
+            #( #variant_names #discriminants, )*
+        }
+    }];
+    let mut initializers = vec![];
+    let mut impls = vec![];
+
+    for (variant, repr_name) in data_enum.variants.iter().zip(&variant_reprs) {
+        let variant_name = &variant.ident;
+        let field_count = variant.fields.len();
+        let field_inits = match FieldInit::parse_all(&variant.fields) {
+            Ok(field_inits) => field_inits,
+            Err(err) => return err.to_compile_error(),
+        };
+        let (generic_names, bindings) = field_bindings(&field_inits);
+        let field_tys: Vec<_> = variant
+            .fields
+            .iter()
+            .map(|f| f.ty.clone())
+            .map(|mut ty| {
+                use syn::visit_mut::VisitMut;
+                visitor.visit_type_mut(&mut ty);
+                ty
+            })
+            .collect();
+        let dsts: Vec<_> = (0..field_count)
+            .map(|n| {
+                let index = syn::Index::from(n + usize::from(tag_in_variant));
+                quote::quote!(&raw mut (*__variant).#index)
+            })
+            .collect();
+        let extra = match ExtraRouting::new(&field_inits) {
+            Ok(extra) => extra,
+            Err(err) => return err.to_compile_error(),
+        };
+        let (extra_generics, extra_type) = (&extra.generics, &extra.ty);
+        let param_extra_tys = extra.param_tys(&field_inits);
+        let init_statements =
+            field_init_statements(&field_inits, &field_tys, &extra, &bindings, &dsts);
+        let param_tys: Vec<_> = field_inits
+            .iter()
+            .zip(&field_tys)
+            .filter(|(field_init, _)| matches!(field_init, FieldInit::Param(_)))
+            .map(|(_, ty)| ty)
+            .collect();
+
+        helper_types.push(quote::quote! {
+            #[allow(dead_code)]
+            #[repr(C)]
+            struct #repr_name #impl_generics (
+                #tag_field
+                #( #field_tys, )*
+                ::core::marker::PhantomData<fn() -> #enum_name #enum_generics>,
+            ) #where_clause;
+        });
+
+        let (initializer_body, initializer_pattern) =
+            initializer_fields(&param_fields(&variant.fields, &field_inits), &generic_names);
+        initializers.push(
+            quote::quote!(#nested_vis struct #variant_name< #( #generic_names, )* > #initializer_body),
+        );
+
+        let variant_ptr = if tag_in_variant {
+            quote::quote! {
+                let __variant = __dst.cast::<#repr_name #enum_generics>();
+                unsafe { (&raw mut (*__variant).0).write(__Tag::#variant_name) };
+            }
+        } else {
+            quote::quote! {
+                let __dst = __dst.cast::<__Repr #enum_generics>();
+                unsafe { (&raw mut (*__dst).tag).write(__Tag::#variant_name) };
+                let __variant = unsafe { &raw mut (*__dst).payload }.cast::<#repr_name #enum_generics>();
+            }
+        };
+        let initializer_ty = quote::quote!(#module_name::#variant_name< #( #generic_names, )* >);
+        let destructure = (!generic_names.is_empty())
+            .then(|| quote::quote!(let #module_name::#variant_name #initializer_pattern = self;));
+
+        impls.push(quote::quote! {
+            unsafe impl<#( #lifetime_params, )* #extra_generics __Error, #( #generic_names, )* #( #impl_params, )* > ::in_place_init::PinInit<#enum_name #enum_generics, __Error, #extra_type> for #initializer_ty
+                where
+                    #( #where_predicates, )*
+                    #( #generic_names: ::in_place_init::PinInit<#param_tys, __Error, #param_extra_tys>, )*
+            {
+                fn metadata(&self) {}
+
+                #[deny(unsafe_op_in_unsafe_fn)]
+                unsafe fn init(self, __dst: *mut #enum_name #enum_generics, __extra: #extra_type) -> Result<(), __Error> {
+                    #destructure
+                    #variant_ptr
+                    #init_statements
+                    Ok(())
+                }
+            }
+
+            unsafe impl<#( #lifetime_params, )* #extra_generics __Error, #( #generic_names, )* #( #impl_params, )* > ::in_place_init::Init<#enum_name #enum_generics, __Error, #extra_type> for #initializer_ty
+                where
+                    #( #where_predicates, )*
+                    #( #generic_names: ::in_place_init::Init<#param_tys, __Error, #param_extra_tys>, )*
+            {}
+        });
+    }
+
+    if repr_c && !variant_reprs.is_empty() {
+        helper_types.push(quote::quote! {
+            #[allow(non_snake_case)]
+            #[repr(C)]
+            union __Payload #impl_generics #where_clause {
+                #( #variant_names: ::core::mem::ManuallyDrop<#variant_reprs #enum_generics>, )*
+            }
+
+            #[allow(dead_code)]
+            #[repr(C)]
+            struct __Repr #impl_generics #where_clause {
+                tag: __Tag,
+                payload: __Payload #enum_generics,
+            }
+        });
+    }
+
+    quote::quote! {
+        #[allow(non_snake_case)]
+        #vis mod #module_name {
+            #( #initializers )*
+        }
+
+        const _: () = {
+            #( #helper_types )*
+
+            #( #impls )*
+        };
+    }
+}
+
+/// Generate the pin projections, the `Unpin` impl, and the `Drop` handling for `#[derive(PinInit)]`.
+#[allow(clippy::too_many_arguments)]
+fn derive_pin_projection(
+    attrs: &[syn::Attribute],
+    options: &InitOptions,
+    fields: &syn::Fields,
+    pinned: &[bool],
+    field_tys: &[syn::Type],
+    vis: &syn::Visibility,
+    struct_name: &syn::Ident,
+    generics: &syn::Generics,
+) -> syn::Result<TokenStream2> {
+    // Fields of packed structs may be unaligned, so they cannot be projected to references.
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("packed") {
+                return Err(meta.error("`#[derive(PinInit)]` is not supported for packed structs"));
+            }
+            if meta.input.peek(syn::token::Paren) {
+                let _: proc_macro2::Group = meta.input.parse()?;
+            }
+            Ok(())
+        })?;
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let where_predicates: Vec<_> = where_clause
+        .map(|wc| wc.predicates.iter().collect())
+        .unwrap_or_default();
+    let mut proj_generics = generics.clone();
+    proj_generics.params.insert(0, syn::parse_quote!('__pin));
+    let (proj_impl_generics, proj_ty_generics, _) = proj_generics.split_for_impl();
+
+    let proj_name = syn::Ident::new(&format!("{struct_name}Proj"), Span::call_site().into());
+    let proj_ref_name = syn::Ident::new(&format!("{struct_name}ProjRef"), Span::call_site().into());
+
+    let members: Vec<_> = fields.members().collect();
+    let field_vis = fields.iter().map(|f| &f.vis);
+    let (proj_tys, proj_ref_tys): (Vec<_>, Vec<_>) = pinned
+        .iter()
+        .zip(field_tys)
+        .map(|(&pinned, ty)| {
+            if pinned {
+                (
+                    quote::quote!(::core::pin::Pin<&'__pin mut #ty>),
+                    quote::quote!(::core::pin::Pin<&'__pin #ty>),
+                )
+            } else {
+                (quote::quote!(&'__pin mut #ty), quote::quote!(&'__pin #ty))
+            }
+        })
+        .unzip();
+    let (proj_values, proj_ref_values): (Vec<_>, Vec<_>) = pinned
+        .iter()
+        .zip(&members)
+        .map(|(&pinned, member)| {
+            if pinned {
+                (
+                    quote::quote!(::core::pin::Pin::new_unchecked(&mut this.#member)),
+                    quote::quote!(::core::pin::Pin::new_unchecked(&this.#member)),
+                )
+            } else {
+                (
+                    quote::quote!(&mut this.#member),
+                    quote::quote!(&this.#member),
+                )
+            }
+        })
+        .unzip();
+
+    let (proj_body, proj_ref_body) = match fields {
+        syn::Fields::Named(_) => {
+            let field_vis: Vec<_> = field_vis.collect();
+            (
+                quote::quote!(#where_clause { #( #field_vis #members: #proj_tys, )* }),
+                quote::quote!(#where_clause { #( #field_vis #members: #proj_ref_tys, )* }),
+            )
+        }
+        _ => {
+            let field_vis: Vec<_> = field_vis.collect();
+            (
+                quote::quote!(( #( #field_vis #proj_tys, )* ) #where_clause;),
+                quote::quote!(( #( #field_vis #proj_ref_tys, )* ) #where_clause;),
+            )
+        }
+    };
+
+    let pinned_fields: Vec<_> = pinned
+        .iter()
+        .zip(field_tys)
+        .filter(|&(&pinned, _)| pinned)
+        .map(|(_, ty)| ty)
+        .collect();
+    let pinned_bindings = (0..pinned_fields.len())
+        .map(|n| syn::Ident::new(&format!("__field{n}"), Span::call_site().into()));
+
+    let drop = if options.pinned_drop {
+        quote::quote! {
+            impl #impl_generics ::core::ops::Drop for #struct_name #ty_generics #where_clause {
+                fn drop(&mut self) {
+                    // SAFETY: the value is being dropped, so it is not moved afterwards even if it was pinned
+                    unsafe { ::in_place_init::PinnedDrop::drop(::core::pin::Pin::new_unchecked(self)) }
+                }
+            }
+        }
+    } else {
+        // Implementing `Drop` would allow moving pinned fields out of `&mut self`, so make it a
+        // conflicting impl.
+        quote::quote! {
+            trait __MustNotImplDrop {}
+            #[allow(drop_bounds)]
+            impl<T: ::core::ops::Drop> __MustNotImplDrop for T {}
+            impl #impl_generics __MustNotImplDrop for #struct_name #ty_generics #where_clause {}
+        }
+    };
+
+    Ok(quote::quote! {
+        #[allow(dead_code)]
+        #vis struct #proj_name #proj_impl_generics #proj_body
+
+        #[allow(dead_code)]
+        #vis struct #proj_ref_name #proj_impl_generics #proj_ref_body
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            /// Project a pinned reference to references to each field, which are pinned for `#[pin]` fields.
+            #vis fn project<'__pin>(self: ::core::pin::Pin<&'__pin mut Self>) -> #proj_name #proj_ty_generics {
+                // SAFETY: only `#[pin]` fields are exposed as pinned, and `Unpin` and `Drop` are only
+                // implemented in ways that respect that.
+                unsafe {
+                    let this = ::core::pin::Pin::get_unchecked_mut(self);
+                    #proj_name { #( #members: #proj_values, )* }
+                }
+            }
+
+            /// Project a pinned shared reference to references to each field, which are pinned for `#[pin]` fields.
+            #vis fn project_ref<'__pin>(self: ::core::pin::Pin<&'__pin Self>) -> #proj_ref_name #proj_ty_generics {
+                // SAFETY: see `project`
+                unsafe {
+                    let this = ::core::pin::Pin::get_ref(self);
+                    #proj_ref_name { #( #members: #proj_ref_values, )* }
+                }
+            }
+        }
+
+        const _: () = {
+            // The value is `Unpin` only if all pinned fields are. The `'__pin` lifetime keeps the
+            // bound from being checked eagerly for fields of concrete types, like `PhantomPinned`.
+            #[allow(dead_code)]
+            struct __Unpin #proj_impl_generics #where_clause {
+                __pin: ::core::marker::PhantomData<&'__pin ()>,
+                __params: ::core::marker::PhantomData<*const #struct_name #ty_generics>,
+                #( #pinned_bindings: #pinned_fields, )*
+            }
+            impl #proj_impl_generics ::core::marker::Unpin for #struct_name #ty_generics
+            where
+                #( #where_predicates, )*
+                __Unpin #proj_ty_generics: ::core::marker::Unpin,
+            {
+            }
+
+            #drop
+        };
+    })
 }
@@ -3,7 +3,7 @@
 
 use std::{fmt, marker::PhantomPinned, mem::MaybeUninit, pin::Pin, ptr::NonNull, rc::Weak};
 
-use in_place_init::{Init, PinInit, VecExt};
+use in_place_init::{Init, PinInit, StringExt, VecExt};
 
 struct SelfReferential {
     value: u32,
@@ -40,6 +40,8 @@ fn main() {
     vec.push_emplace(in_place_init::array_repeat(vec.len()));
     vec.push_emplace(in_place_init::array_repeat(vec.len()));
     assert_eq!(vec, [[0; 100], [1; 100]]);
+    vec.insert_emplace(1, in_place_init::array_repeat(2));
+    assert_eq!(vec, [[0; 100], [2; 100], [1; 100]]);
 
     let bx = in_place_init::new_boxed::<str>("hello, world");
     dbg!(bx);
@@ -53,28 +55,55 @@ fn main() {
     let rc = in_place_init::rc_new_cyclic(in_place_init::with(|weak| Foo { weak }));
     dbg!(rc);
 
-    #[derive(Debug)]
-    #[allow(unused)]
-    struct Bar {
-        idx: usize,
-        weak: Weak<[Bar]>,
-    }
+    // Cyclic slices rely on the layout of `Rc`'s heap allocation
+    #[cfg(feature = "assume-rc-layout")]
+    {
+        #[derive(Debug)]
+        #[allow(unused)]
+        struct Bar {
+            idx: usize,
+            weak: Weak<[Bar]>,
+        }
 
-    let rc = in_place_init::rc_new_cyclic(in_place_init::slice_for_each(2, |idx| {
-        in_place_init::with(move |weak| Bar { idx, weak })
-    }));
-    dbg!(rc);
+        let rc = in_place_init::rc_new_cyclic(in_place_init::slice_for_each(2, |idx| {
+            in_place_init::with(move |weak| Bar { idx, weak })
+        }));
+        dbg!(rc);
+
+        let rc =
+            in_place_init::rc_new_cyclic(in_place_init::slice_for_each_with(2, |idx, weak| Bar {
+                idx,
+                weak,
+            }));
+        dbg!(rc);
+
+        #[derive(Debug)]
+        #[allow(unused)]
+        struct Node {
+            idx: usize,
+            weak: std::sync::Weak<[Node]>,
+        }
 
-    let rc = in_place_init::rc_new_cyclic(in_place_init::slice_for_each_with(2, |idx, weak| Bar {
-        idx,
-        weak,
-    }));
-    dbg!(rc);
+        let arc =
+            in_place_init::arc_new_cyclic(in_place_init::slice_for_each_with(3, |idx, weak| {
+                Node { idx, weak }
+            }));
+        let arc2 = std::thread::spawn({
+            let weak = arc[1].weak.clone();
+            move || weak.upgrade().unwrap()
+        })
+        .join()
+        .unwrap();
+        assert!(std::sync::Arc::ptr_eq(&arc, &arc2));
+    }
 
     let rc =
         in_place_init::rc_new::<[String]>(in_place_init::slice_for_each(42, |x| format!("{x}")));
     println!("{rc:?}");
 
+    let arc: std::sync::Arc<str> = in_place_init::arc_new(in_place_init::chain("hello, ", "arc"));
+    assert_eq!(&*arc, "hello, arc");
+
     _ = std::panic::catch_unwind(|| {
         let rc = in_place_init::try_rc_new::<[String], _>(in_place_init::slice_for_each(42, |x| {
             if x == 30 {
@@ -119,39 +148,113 @@ fn main() {
     let bx: Box<str> = in_place_init::new_boxed(in_place_init::chain("hello", "world"));
     assert_eq!(&*bx, "helloworld");
 
+    let mut s = String::from("1 + 2 = ");
+    s.append_emplace(in_place_init::format_str(format_args!("{}", 1 + 2)));
+    let bx: Box<str> = in_place_init::new_boxed(in_place_init::chain(
+        in_place_init::format_str(format_args!("{s}; ")),
+        in_place_init::format_str(format_args!("{:?}", [4, 5])),
+    ));
+    assert_eq!(&*bx, "1 + 2 = 3; [4, 5]");
+
+    let arena = in_place_init::Arena::new();
+    let name: &mut str = arena.emplace(in_place_init::format_str(format_args!("node{}", 7)));
+    let children: &mut [u32] = arena.emplace(in_place_init::slice_repeat(3, 0));
+    let bx = in_place_init::new_boxed_in(42u64, &arena);
+    assert_eq!((&*name, &*children, *bx), ("node7", &[0, 0, 0][..], 42));
+
+    struct Packet<T: ?Sized> {
+        id: u32,
+        tag: String,
+        payload: T,
+    }
+    let bx: Box<Packet<[u8]>> = in_place_init::new_boxed(in_place_init::init!(Packet::<_> {
+        id: 1,
+        tag: String::from("ping"),
+        payload <- in_place_init::slice_repeat(4, 0xAA),
+    }));
+    assert_eq!((bx.id, &*bx.tag, &bx.payload), (1, "ping", &[0xAA; 4][..]));
+
+    let bx: Box<u32> = in_place_init::new_boxed(in_place_init::or("x".parse::<u32>(), 7u32));
+    assert_eq!(*bx, 7);
+    let bx: Box<str> = in_place_init::new_boxed(in_place_init::or_else(
+        in_place_init::as_utf8::<[u8; 2]>([0xC3, 0x28]),
+        |_: std::str::Utf8Error| "??",
+    ));
+    assert_eq!(&*bx, "??");
+
     #[cfg(feature = "macros")]
     {
         use std::rc::Rc;
 
-        let bx: Box<foo::Bar<42, str>> = in_place_init::new_boxed(foo::BarInit(
-            in_place_init::array_for_each(|idx| idx as u8),
-            "hello, world!",
-        ));
+        let bx: Box<foo::Bar<42, str>> = in_place_init::new_boxed(foo::BarInit {
+            x: in_place_init::array_for_each(|idx| idx as u8),
+            y: "hello, world!",
+        });
         println!("{bx:?}");
 
-        let rc1: Rc<foo::Baz<str>> = in_place_init::rc_new_cyclic(foo::BazInit(
-            in_place_init::with(|weak| weak),
-            in_place_init::ignore_extra("hello, world!"),
-        ));
-        let rc2 = rc1.this.upgrade().unwrap();
-        println!("{rc1:?}");
-        assert!(Rc::ptr_eq(&rc1, &rc2));
+        #[cfg(feature = "assume-rc-layout")]
+        {
+            let rc1: Rc<foo::Baz<str>> = in_place_init::rc_new_cyclic(foo::BazInit {
+                this: in_place_init::with(|weak| weak),
+                tail: "hello, world!",
+            });
+            let rc2 = rc1.this.upgrade().unwrap();
+            println!("{rc1:?}");
+            assert!(Rc::ptr_eq(&rc1, &rc2));
+        }
 
-        let bx: Box<foo::Bar<20, dyn std::any::Any>> = in_place_init::new_boxed(foo::BarInit(
-            in_place_init::array_for_each({
+        let bx: Box<foo::Bar<20, dyn std::any::Any>> = in_place_init::new_boxed(foo::BarInit {
+            x: in_place_init::array_for_each({
                 let mut acc = 0;
                 move |idx| {
                     acc += idx;
                     acc as u8
                 }
             }),
-            in_place_init::unsize(String::from("hello, world")),
-        ));
+            y: in_place_init::unsize(String::from("hello, world")),
+        });
         println!("{bx:?}");
         assert_eq!(
             bx.y.downcast_ref::<String>().map(String::as_str),
             Some("hello, world")
         );
+
+        let bx: Box<foo::Message<[u8; 4]>> = in_place_init::new_boxed(foo::MessageInit::Data(
+            5,
+            in_place_init::array_for_each(|idx| idx as u8),
+        ));
+        assert_eq!(*bx, foo::Message::Data(5, [0, 1, 2, 3]));
+        let bx: Box<foo::Message<()>> = in_place_init::new_boxed(foo::MessageInit::Text {
+            len: 3,
+            body: String::from("abc"),
+        });
+        assert_eq!(
+            *bx,
+            foo::Message::Text {
+                len: 3,
+                body: String::from("abc")
+            }
+        );
+        let bx: Box<foo::Message<()>> = in_place_init::new_boxed(foo::MessageInit::Ping);
+        assert_eq!(*bx, foo::Message::Ping);
+
+        let bx: Box<foo::Shape<'_>> = in_place_init::new_boxed(foo::ShapeInit::Label {
+            name: "label",
+            size: [1, 2, 3],
+        });
+        println!("{bx:?}");
+
+        let bx: Box<foo::Point> = in_place_init::new_boxed(foo::Point::init().y(2).x(1));
+        assert_eq!((bx.x, bx.y), (1, 2));
+
+        let bx: Box<foo::Node> = in_place_init::new_boxed(foo::NodeInit {
+            name: "root",
+            children: in_place_init::array_for_each(|_| None),
+        });
+        assert_eq!(
+            (bx.name, bx.depth, bx.weight, bx.children.len()),
+            ("root", 0, 1.0, 4)
+        );
     }
 }
 
@@ -168,8 +271,44 @@ mod foo {
         pub y: T,
     }
 
+    #[derive(Init)]
+    #[init(builder, vis = pub(crate))]
+    pub(crate) struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    #[derive(Init)]
+    pub(crate) struct Node {
+        pub name: &'static str,
+        #[init(default)]
+        pub depth: usize,
+        #[init(value = 1.0)]
+        pub weight: f64,
+        #[init(uninit)]
+        pub scratch: std::mem::MaybeUninit<[u8; 64]>,
+        pub children: [Option<Box<Node>>; 4],
+    }
+
+    #[derive(Debug, PartialEq, Init)]
+    #[repr(u8)]
+    pub(crate) enum Message<T> {
+        Ping,
+        Data(u32, T),
+        Text { len: usize, body: String } = 7,
+    }
+
+    #[derive(Debug, Init)]
+    #[repr(C)]
+    pub(crate) enum Shape<'a> {
+        Circle(f64),
+        Label { name: &'a str, size: [u16; 3] },
+        Empty,
+    }
+
     #[derive(Debug, Init)]
     pub(crate) struct Baz<T: ?Sized> {
+        #[init(extra)]
         pub this: std::rc::Weak<Self>,
         pub tail: T,
     }
@@ -1,5 +1,8 @@
+#![feature(ptr_metadata)]
 #![allow(unused)]
-use std::mem::MaybeUninit;
+use std::{fmt::Write, mem::MaybeUninit, pin::Pin};
+
+use in_place_init::StaticSlot;
 
 mod sbo {
     use std::{
@@ -45,6 +48,9 @@ mod sbo {
         }
     }
 
+    // SAFETY: `ShortBuffer` owns its elements like a `Vec<T>` does, whether they are inline or on the heap.
+    unsafe impl<const N: usize, T: Send> Send for ShortBuffer<N, T> {}
+
     impl<const N: usize, T> Drop for ShortBuffer<N, T> {
         fn drop(&mut self) {
             // drop elements
@@ -357,7 +363,7 @@ mod sso {
     use core::fmt;
     use std::pin::Pin;
 
-    #[pin_project::pin_project]
+    #[derive(in_place_init::PinInit)]
     #[repr(transparent)]
     pub struct ShortString<const N: usize>(#[pin] crate::sbo::ShortBuffer<N, u8>);
 
@@ -405,13 +411,12 @@ mod sso {
 }
 fn main() {
     let mut buffer = {
-        static mut BUFFER: MaybeUninit<sbo::ShortBuffer<4, String>> = MaybeUninit::uninit();
-        in_place_init::initialize_pinned_owned(
-            unsafe { &mut *&raw mut BUFFER },
-            sbo::MakeShortBuffer {
+        static BUFFER: StaticSlot<sbo::ShortBuffer<4, String>> = StaticSlot::new();
+        BUFFER
+            .take_and_init_owned(sbo::MakeShortBuffer {
                 slice_initializer: [String::from("hello"), String::from("world")],
-            },
-        )
+            })
+            .unwrap()
     };
 
     println!("{buffer:?}");
@@ -429,4 +434,14 @@ fn main() {
     buffer.as_mut().push("friend".to_owned());
     println!("{buffer:?}");
     drop(buffer);
+
+    in_place_init::stack_pin_init!(let buffer: Pin<&mut sbo::ShortBuffer<4, String>> = sbo::MakeShortBuffer {
+        slice_initializer: [String::from("on"), String::from("the")],
+    });
+    buffer.as_mut().push("stack".to_owned());
+    println!("{buffer:?}");
+
+    in_place_init::stack_pin_init!(let string: Pin<&mut sso::ShortString<8>> = sso::ShortStringInit(sbo::MakeEmptyShortBuffer));
+    write!(string, "hello, world!").unwrap();
+    assert_eq!(string.as_str(), "hello, world!");
 }
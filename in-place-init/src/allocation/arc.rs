@@ -0,0 +1,485 @@
+use core::{alloc::Layout, marker::MetaSized, mem::MaybeUninit, pin::Pin};
+
+use alloc::alloc::{Allocator, Global};
+pub(crate) use alloc::sync::{Arc, UniqueArc, Weak};
+
+use crate::{Init, InitFromIterError, PinInit};
+
+use super::{error::AllocOrInit, layout::layout_for_metadata};
+
+/// Types that an `Arc` can be emplaced into.
+///
+/// Without the `assume-rc-layout` feature, this is implemented for sized types, slices, and `str`,
+/// which are allocated through `Arc`'s public API (e.g. [`Arc::new_uninit_in`]).
+///
+/// With the `assume-rc-layout` feature, this is implemented for all types, by constructing
+/// `Arc`'s heap allocation manually. Note that this relies on the layout of `Arc`'s heap allocation,
+/// which is not stable.
+///
+/// # Safety
+///
+/// This trait should not be implemented outside of this crate.
+pub unsafe trait ArcDst: MetaSized {
+    /// # Safety
+    ///
+    /// Either `init` implements `Init`, or the returned `Arc` is treated as pinned.
+    #[doc(hidden)]
+    unsafe fn arc_new_impl<Error, A: Allocator, Extra>(
+        init: impl PinInit<Self, Error, Extra>,
+        alloc: A,
+        extra: Extra,
+    ) -> Result<Arc<Self, A>, AllocOrInit<Error>>;
+}
+
+/// Types that an `Arc` can be emplaced into while handing out `Weak`s to the allocation.
+///
+/// Without the `assume-rc-layout` feature, this is implemented for sized types,
+/// which are allocated through [`UniqueArc`](alloc::sync::UniqueArc).
+///
+/// With the `assume-rc-layout` feature, this is implemented for all types. See [`ArcDst`].
+///
+/// # Safety
+///
+/// This trait should not be implemented outside of this crate.
+pub unsafe trait ArcCyclicDst: ArcDst {
+    /// # Safety
+    ///
+    /// Either `init` implements `Init`, or the returned `Arc` and the `Weak`s passed to `make_extra`
+    /// are treated as pinned.
+    #[doc(hidden)]
+    unsafe fn arc_new_cyclic_impl<Error, A: Allocator + Clone, Extra>(
+        init: impl PinInit<Self, Error, Extra>,
+        alloc: A,
+        make_extra: impl FnOnce(&Weak<Self, A>) -> Extra,
+    ) -> Result<Arc<Self, A>, AllocOrInit<Error>>;
+}
+
+#[cfg(not(feature = "assume-rc-layout"))]
+unsafe impl<T> ArcDst for T {
+    unsafe fn arc_new_impl<Error, A: Allocator, Extra>(
+        init: impl PinInit<T, Error, Extra>,
+        alloc: A,
+        extra: Extra,
+    ) -> Result<Arc<T, A>, AllocOrInit<Error>> {
+        let mut arc = Arc::<T, A>::try_new_uninit_in(alloc)
+            .map_err(|_| AllocOrInit::Alloc(Layout::new::<T>()))?;
+        let dst = Arc::get_mut(&mut arc).unwrap().as_mut_ptr();
+        // dropping `arc` on error or panic deallocates without dropping the value
+        unsafe { init.init(dst, extra) }.map_err(AllocOrInit::Init)?;
+        // SAFETY: we just initialized the value
+        Ok(unsafe { arc.assume_init() })
+    }
+}
+
+#[cfg(not(feature = "assume-rc-layout"))]
+unsafe impl<T> ArcDst for [T] {
+    unsafe fn arc_new_impl<Error, A: Allocator, Extra>(
+        init: impl PinInit<[T], Error, Extra>,
+        alloc: A,
+        extra: Extra,
+    ) -> Result<Arc<[T], A>, AllocOrInit<Error>> {
+        let len = init.metadata();
+        // There is no fallible `new_uninit_slice_in`, so only layout overflow is surfaced;
+        // allocation failure still aborts.
+        check_arc_layout(layout_for_metadata::<[T]>(len)?)?;
+        let mut arc = Arc::<[T], A>::new_uninit_slice_in(len, alloc);
+        let dst = Arc::get_mut(&mut arc).unwrap() as *mut [_] as *mut [T];
+        // dropping `arc` on error or panic deallocates without dropping the elements
+        unsafe { init.init(dst, extra) }.map_err(AllocOrInit::Init)?;
+        // SAFETY: we just initialized the elements
+        Ok(unsafe { arc.assume_init() })
+    }
+}
+
+#[cfg(not(feature = "assume-rc-layout"))]
+unsafe impl ArcDst for str {
+    unsafe fn arc_new_impl<Error, A: Allocator, Extra>(
+        init: impl PinInit<str, Error, Extra>,
+        alloc: A,
+        extra: Extra,
+    ) -> Result<Arc<str, A>, AllocOrInit<Error>> {
+        let len = init.metadata();
+        // See the `[T]` impl above.
+        check_arc_layout(layout_for_metadata::<str>(len)?)?;
+        let mut arc = Arc::<[u8], A>::new_uninit_slice_in(len, alloc);
+        let dst = Arc::get_mut(&mut arc).unwrap() as *mut [_] as *mut str;
+        // dropping `arc` on error or panic deallocates
+        unsafe { init.init(dst, extra) }.map_err(AllocOrInit::Init)?;
+        // SAFETY: we just initialized the bytes
+        let (ptr, alloc) = Arc::into_raw_with_allocator(unsafe { arc.assume_init() });
+        // SAFETY: `str` has the same layout as `[u8]`, and `init` wrote valid UTF-8
+        Ok(unsafe { Arc::from_raw_in(ptr as *const str, alloc) })
+    }
+}
+
+#[cfg(not(feature = "assume-rc-layout"))]
+unsafe impl<T> ArcCyclicDst for T {
+    unsafe fn arc_new_cyclic_impl<Error, A: Allocator + Clone, Extra>(
+        init: impl PinInit<T, Error, Extra>,
+        alloc: A,
+        make_extra: impl FnOnce(&Weak<T, A>) -> Extra,
+    ) -> Result<Arc<T, A>, AllocOrInit<Error>> {
+        // There is no fallible `UniqueArc::new_in`, so allocation failure aborts.
+        // SAFETY: discharged to caller
+        let uninit = unsafe {
+            unique_arc_new_uninit_impl(init, alloc, |uninit| make_extra(&downgrade_uninit(uninit)))
+        }
+        .map_err(AllocOrInit::Init)?;
+        // SAFETY: the value is initialized
+        Ok(unsafe { UniqueArc::into_arc(uninit).assume_init() })
+    }
+}
+
+/// Checks that the size of `Arc`'s heap allocation (two counts followed by the value) does not overflow.
+#[cfg(not(feature = "assume-rc-layout"))]
+fn check_arc_layout(value_layout: Layout) -> Result<(), core::alloc::LayoutError> {
+    Layout::new::<[usize; 2]>().extend(value_layout)?;
+    Ok(())
+}
+
+/// Emplace a value into a new `UniqueArc`, which is returned still wrapped in `MaybeUninit`.
+///
+/// `Weak`s to a `UniqueArc` cannot be upgraded until it is converted into an `Arc`,
+/// so they can be handed out to `init` through `make_extra`.
+///
+/// # Safety
+///
+/// Either `init` implements `Init`, or the value and the `Weak`s created in `make_extra`
+/// are treated as pinned.
+///
+/// If this returns `Ok(_)`, the value is initialized.
+#[cfg(not(feature = "assume-rc-layout"))]
+unsafe fn unique_arc_new_uninit_impl<T, Error, A: Allocator, Extra>(
+    init: impl PinInit<T, Error, Extra>,
+    alloc: A,
+    make_extra: impl FnOnce(&UniqueArc<MaybeUninit<T>, A>) -> Extra,
+) -> Result<UniqueArc<MaybeUninit<T>, A>, Error> {
+    // `UniqueArc` is the only way to hand out `Weak`s before the value is initialized, but it
+    // has no `new_uninit_in`, so the uninitialized `MaybeUninit<T>` is passed by value and may
+    // be materialized on the stack for large `T`. Non-cyclic `Arc`s use `Arc::try_new_uninit_in`.
+    let mut uninit = UniqueArc::new_in(MaybeUninit::<T>::uninit(), alloc);
+    let extra = make_extra(&uninit);
+    // dropping `uninit` on error or panic deallocates (or leaves the allocation to the
+    // remaining `Weak`s) without dropping the value
+    unsafe { init.init(uninit.as_mut_ptr(), extra) }?;
+    Ok(uninit)
+}
+
+#[cfg(not(feature = "assume-rc-layout"))]
+fn downgrade_uninit<T, A: Allocator + Clone>(uninit: &UniqueArc<MaybeUninit<T>, A>) -> Weak<T, A> {
+    let (weak, alloc) = Weak::into_raw_with_allocator(UniqueArc::downgrade(uninit));
+    // SAFETY: `MaybeUninit<T>` has the same size and alignment as `T`
+    unsafe { Weak::from_raw_in(weak.cast::<T>(), alloc) }
+}
+
+#[cfg(feature = "assume-rc-layout")]
+unsafe impl<T: MetaSized> ArcDst for T {
+    unsafe fn arc_new_impl<Error, A: Allocator, Extra>(
+        init: impl PinInit<T, Error, Extra>,
+        alloc: A,
+        extra: Extra,
+    ) -> Result<Arc<T, A>, AllocOrInit<Error>> {
+        // SAFETY: discharged to caller
+        unsafe { unique_arc_new_layout_impl(init, alloc, |_| extra) }.map(UniqueArc::into_arc)
+    }
+}
+
+#[cfg(feature = "assume-rc-layout")]
+unsafe impl<T: MetaSized> ArcCyclicDst for T {
+    unsafe fn arc_new_cyclic_impl<Error, A: Allocator + Clone, Extra>(
+        init: impl PinInit<T, Error, Extra>,
+        alloc: A,
+        make_extra: impl FnOnce(&Weak<T, A>) -> Extra,
+    ) -> Result<Arc<T, A>, AllocOrInit<Error>> {
+        // SAFETY: discharged to caller
+        unsafe { unique_arc_new_layout_impl(init, alloc, make_extra) }.map(UniqueArc::into_arc)
+    }
+}
+
+/// Emplace a value into a new `UniqueArc`, while handing out `Weak`s to it through `make_extra`.
+///
+/// # Safety
+///
+/// Either `init` implements `Init`, or the returned `UniqueArc` and `sync::Weak`s passed to
+/// `make_extra` are treated as pinned.
+///
+/// Also, this assumes the layout of `Arc`'s heap allocation, and that `UniqueArc<T, A>` has the
+/// same layout as `Weak<T, A>`, neither of which is stable.
+#[cfg(feature = "assume-rc-layout")]
+pub(crate) unsafe fn unique_arc_new_layout_impl<T: MetaSized, Error, A: Allocator, Extra>(
+    init: impl PinInit<T, Error, Extra>,
+    alloc: A,
+    make_extra: impl FnOnce(&Weak<T, A>) -> Extra,
+) -> Result<UniqueArc<T, A>, AllocOrInit<Error>> {
+    // NOTE: this is unsound; it relies on the unstable layout of Arc's heap allocation
+    use core::sync::atomic::AtomicUsize;
+    let metadata = init.metadata();
+    let value_layout = layout_for_metadata::<T>(metadata)?;
+
+    #[repr(C)]
+    struct ArcCounts {
+        strong: AtomicUsize,
+        weak: AtomicUsize,
+    }
+
+    let (layout, offset) = Layout::new::<ArcCounts>().extend(value_layout)?;
+
+    let base_ptr = if layout.size() == 0 {
+        layout.dangling()
+    } else {
+        match alloc.allocate(layout) {
+            Ok(ptr) => ptr.cast(),
+            Err(_) => return Err(AllocOrInit::Alloc(layout)),
+        }
+    };
+
+    unsafe {
+        base_ptr.cast::<ArcCounts>().write(ArcCounts {
+            strong: 0.into(),
+            weak: 1.into(),
+        });
+    }
+
+    let value_ptr =
+        core::ptr::from_raw_parts_mut::<T>(unsafe { base_ptr.byte_add(offset).as_ptr() }, metadata);
+
+    let weak = unsafe { Weak::from_raw_in(value_ptr, alloc) };
+
+    let extra = make_extra(&weak);
+
+    match unsafe { init.init(value_ptr, extra) } {
+        // Like `weak`, a `UniqueArc` holds the implicit weak reference while the strong count is 0,
+        // so it takes over `weak`. `UniqueArc::into_arc` then publishes the value to `Weak::upgrade`
+        // on other threads, like `Arc::new_cyclic`.
+        // NOTE: there is no `UniqueArc::from_raw_in`, so this relies on the layout of `UniqueArc`
+        Ok(()) => Ok(unsafe {
+            const { assert!(size_of::<Weak<T, A>>() == size_of::<UniqueArc<T, A>>()) };
+            core::ptr::from_ref(&*core::mem::ManuallyDrop::new(weak))
+                .cast::<UniqueArc<T, A>>()
+                .read()
+        }),
+        // dropping `weak` in this branch deallocates
+        Err(err) => Err(AllocOrInit::Init(err)),
+    }
+}
+
+pub fn try_arc_new<T: MetaSized + ArcDst, Error>(
+    init: impl Init<T, Error>,
+) -> Result<Arc<T>, Error> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { T::arc_new_impl(init, Global, ()) }.map_err(AllocOrInit::handle_alloc_error)
+}
+pub fn arc_new<T: MetaSized + ArcDst>(init: impl Init<T>) -> Arc<T> {
+    try_arc_new(init).unwrap_or_else(|e| match e {})
+}
+/// Collect an iterator into a new `Arc<[T]>`, without an intermediate `Vec`.
+pub fn try_arc_new_from_iter<T>(
+    iter: impl IntoIterator<IntoIter: ExactSizeIterator<Item = T>>,
+) -> Result<Arc<[T]>, InitFromIterError> {
+    try_arc_new(crate::from_iter(iter))
+}
+/// Collect an iterator into a new `Arc<[T]>`, without an intermediate `Vec`.
+///
+/// # Panics
+///
+/// Panics if the iterator does not yield exactly as many items as its `len()`.
+pub fn arc_new_from_iter<T>(
+    iter: impl IntoIterator<IntoIter: ExactSizeIterator<Item = T>>,
+) -> Arc<[T]> {
+    try_arc_new_from_iter(iter).unwrap_or_else(|e| panic!("{e}"))
+}
+pub fn try_arc_new_pinned<T: MetaSized + ArcDst, Error>(
+    init: impl PinInit<T, Error>,
+) -> Result<Pin<Arc<T>>, Error> {
+    // Safety: the `Arc` is immediately pinned
+    let arc =
+        unsafe { T::arc_new_impl(init, Global, ()) }.map_err(AllocOrInit::handle_alloc_error)?;
+    // SAFETY: No other code has had access to this `Arc`.
+    Ok(unsafe { Pin::new_unchecked(arc) })
+}
+pub fn arc_new_pinned<T: MetaSized + ArcDst>(init: impl PinInit<T>) -> Pin<Arc<T>> {
+    try_arc_new_pinned(init).unwrap_or_else(|e| match e {})
+}
+
+/// Create a new `Arc<T>` while giving you a `Weak<T>` to the allocation.
+pub fn try_arc_new_cyclic<T: MetaSized + ArcCyclicDst, Error>(
+    init: impl Init<T, Error, Weak<T>>,
+) -> Result<Arc<T>, Error> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { T::arc_new_cyclic_impl(init, Global, Weak::clone) }
+        .map_err(AllocOrInit::handle_alloc_error)
+}
+
+/// Create a new `Arc<T>` while giving you a `Weak<T>` to the allocation.
+pub fn arc_new_cyclic<T: MetaSized + ArcCyclicDst>(init: impl Init<T, !, Weak<T>>) -> Arc<T> {
+    try_arc_new_cyclic(init).unwrap_or_else(|e| match e {})
+}
+
+/// Create a new pinned `Arc<T>` while giving you a `Weak<T>` to the allocation.
+///
+/// # Safety
+///
+/// `init` must treat the `Weak`s passed to it as pinned.
+pub unsafe fn try_arc_new_cyclic_pinned<T: MetaSized + ArcCyclicDst, Error>(
+    init: impl PinInit<T, Error, Weak<T>>,
+) -> Result<Pin<Arc<T>>, Error> {
+    // Safety: the `Arc` is immediately pinned
+    let arc = unsafe { T::arc_new_cyclic_impl(init, Global, Weak::clone) }
+        .map_err(AllocOrInit::handle_alloc_error)?;
+    // SAFETY: The only code that has had access to this Arc has had access as `Weak<T>`,
+    // which the caller must ensure are treated as pinned.
+    Ok(unsafe { Pin::new_unchecked(arc) })
+}
+
+/// Create a new pinned `Arc<T>` while giving you a `Weak<T>` to the allocation.
+///
+/// # Safety
+///
+/// `init` must treat the `Weak`s passed to it as pinned.
+pub unsafe fn arc_new_cyclic_pinned<T: MetaSized + ArcCyclicDst>(
+    init: impl PinInit<T, !, Weak<T>>,
+) -> Pin<Arc<T>> {
+    // SAFETY: discharged to caller
+    unsafe { try_arc_new_cyclic_pinned(init).unwrap_or_else(|e| match e {}) }
+}
+
+/// Like [`try_arc_new`], but surfaces allocation failure instead of aborting.
+///
+/// Without the `assume-rc-layout` feature, allocation failure for slices and `str` still aborts,
+/// since `Arc` has no fallible API to allocate them.
+pub fn checked_arc_new<T: MetaSized + ArcDst, Error>(
+    init: impl Init<T, Error>,
+) -> Result<Arc<T>, AllocOrInit<Error>> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { T::arc_new_impl(init, Global, ()) }
+}
+/// Like [`try_arc_new_pinned`], but surfaces allocation failure instead of aborting.
+///
+/// See [`checked_arc_new`].
+pub fn checked_arc_new_pinned<T: MetaSized + ArcDst, Error>(
+    init: impl PinInit<T, Error>,
+) -> Result<Pin<Arc<T>>, AllocOrInit<Error>> {
+    // Safety: the `Arc` is immediately pinned
+    let arc = unsafe { T::arc_new_impl(init, Global, ()) }?;
+    // SAFETY: No other code has had access to this `Arc`.
+    Ok(unsafe { Pin::new_unchecked(arc) })
+}
+
+/// Like [`try_arc_new_cyclic`], but surfaces allocation failure instead of aborting.
+///
+/// Without the `assume-rc-layout` feature, allocation failure still aborts,
+/// since `UniqueArc` has no fallible API.
+pub fn checked_arc_new_cyclic<T: MetaSized + ArcCyclicDst, Error>(
+    init: impl Init<T, Error, Weak<T>>,
+) -> Result<Arc<T>, AllocOrInit<Error>> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { T::arc_new_cyclic_impl(init, Global, Weak::clone) }
+}
+
+/// Like [`try_arc_new_cyclic_pinned`], but surfaces allocation failure instead of aborting.
+///
+/// See [`checked_arc_new_cyclic`].
+///
+/// # Safety
+///
+/// `init` must treat the `Weak`s passed to it as pinned.
+pub unsafe fn checked_arc_new_cyclic_pinned<T: MetaSized + ArcCyclicDst, Error>(
+    init: impl PinInit<T, Error, Weak<T>>,
+) -> Result<Pin<Arc<T>>, AllocOrInit<Error>> {
+    // Safety: the `Arc` is immediately pinned
+    let arc = unsafe { T::arc_new_cyclic_impl(init, Global, Weak::clone) }?;
+    // SAFETY: The only code that has had access to this Arc has had access as `Weak<T>`,
+    // which the caller must ensure are treated as pinned.
+    Ok(unsafe { Pin::new_unchecked(arc) })
+}
+
+/// Create a new `UniqueArc<T>`.
+#[cfg(feature = "assume-rc-layout")]
+pub fn try_unique_arc_new<T: MetaSized, Error>(
+    init: impl Init<T, Error>,
+) -> Result<UniqueArc<T>, Error> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { unique_arc_new_layout_impl(init, Global, |_| ()) }
+        .map_err(AllocOrInit::handle_alloc_error)
+}
+
+/// Create a new `UniqueArc<T>`.
+#[cfg(feature = "assume-rc-layout")]
+pub fn unique_arc_new<T: MetaSized>(init: impl Init<T>) -> UniqueArc<T> {
+    try_unique_arc_new(init).unwrap_or_else(|e| match e {})
+}
+
+/// Create a new `UniqueArc<T>` while giving you a `Weak<T>` to the allocation.
+///
+/// The `Weak`s cannot be upgraded until the `UniqueArc` is converted into an `Arc`.
+#[cfg(feature = "assume-rc-layout")]
+pub fn try_unique_arc_new_cyclic<T: MetaSized, Error>(
+    init: impl Init<T, Error, Weak<T>>,
+) -> Result<UniqueArc<T>, Error> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { unique_arc_new_layout_impl(init, Global, Weak::clone) }
+        .map_err(AllocOrInit::handle_alloc_error)
+}
+
+/// Create a new `UniqueArc<T>` while giving you a `Weak<T>` to the allocation.
+///
+/// The `Weak`s cannot be upgraded until the `UniqueArc` is converted into an `Arc`.
+#[cfg(feature = "assume-rc-layout")]
+pub fn unique_arc_new_cyclic<T: MetaSized>(init: impl Init<T, !, Weak<T>>) -> UniqueArc<T> {
+    try_unique_arc_new_cyclic(init).unwrap_or_else(|e| match e {})
+}
+
+/// Initialize the value in an `Arc<MaybeUninit<T>>`.
+///
+/// If `init` fails or panics, the `Arc` is dropped.
+///
+/// # Panics
+///
+/// Panics if there are other `Arc` or `Weak` pointers to the same allocation.
+pub fn try_init_arc<T, Error, A: Allocator>(
+    mut arc: Arc<MaybeUninit<T>, A>,
+    init: impl Init<T, Error>,
+) -> Result<Arc<T, A>, Error> {
+    let slot = Arc::get_mut(&mut arc).expect("the `Arc` is not unique");
+    crate::try_initialize(slot, init)?;
+    // SAFETY: we just initialized the value
+    Ok(unsafe { arc.assume_init() })
+}
+/// Initialize the value in an `Arc<MaybeUninit<T>>`.
+///
+/// # Panics
+///
+/// Panics if there are other `Arc` or `Weak` pointers to the same allocation.
+pub fn init_arc<T, A: Allocator>(arc: Arc<MaybeUninit<T>, A>, init: impl Init<T>) -> Arc<T, A> {
+    try_init_arc(arc, init).unwrap_or_else(|e| match e {})
+}
+
+/// Initialize the elements in an `Arc<[MaybeUninit<T>]>`.
+///
+/// If `init` fails or panics, the `Arc` is dropped.
+///
+/// # Panics
+///
+/// Panics if there are other `Arc` or `Weak` pointers to the same allocation, or if `init`'s
+/// length does not match the length of the slice.
+pub fn try_init_arc_slice<T, Error, A: Allocator>(
+    mut arc: Arc<[MaybeUninit<T>], A>,
+    init: impl Init<[T], Error>,
+) -> Result<Arc<[T], A>, Error> {
+    let slot = Arc::get_mut(&mut arc).expect("the `Arc` is not unique");
+    crate::try_initialize_slice(slot, init)?;
+    // SAFETY: we just initialized the elements
+    Ok(unsafe { arc.assume_init() })
+}
+/// Initialize the elements in an `Arc<[MaybeUninit<T>]>`.
+///
+/// # Panics
+///
+/// Panics if there are other `Arc` or `Weak` pointers to the same allocation, or if `init`'s
+/// length does not match the length of the slice.
+pub fn init_arc_slice<T, A: Allocator>(
+    arc: Arc<[MaybeUninit<T>], A>,
+    init: impl Init<[T]>,
+) -> Arc<[T], A> {
+    try_init_arc_slice(arc, init).unwrap_or_else(|e| match e {})
+}
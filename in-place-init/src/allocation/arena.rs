@@ -0,0 +1,336 @@
+use core::{
+    alloc::{AllocError, Layout},
+    cell::Cell,
+    marker::{MetaSized, PhantomData},
+    num::NonZero,
+    pin::Pin,
+    ptr::NonNull,
+};
+
+use alloc::alloc::{Allocator, Global};
+
+use crate::{Init, PinInit};
+
+use super::{error::AllocOrInit, layout::layout_for_metadata};
+
+/// The smallest chunk the arena will allocate from its backing allocator.
+const MIN_CHUNK_SIZE: usize = 4096;
+
+/// Stored at the start of each chunk, linking it to the previously allocated chunk.
+struct ChunkHeader {
+    prev: Option<NonNull<ChunkHeader>>,
+    layout: Layout,
+}
+
+/// A type-erased destructor for an emplaced value, linking it to the previously registered one.
+struct DropHeader {
+    prev: Option<NonNull<DropHeader>>,
+    drop: unsafe fn(NonNull<DropHeader>),
+}
+
+#[repr(C)]
+struct DropEntry<T: MetaSized> {
+    header: DropHeader,
+    value: *mut T,
+}
+
+/// # Safety
+///
+/// `header` must point to the header of a `DropEntry<T>` whose value is initialized and not used afterwards.
+unsafe fn drop_entry<T: MetaSized>(header: NonNull<DropHeader>) {
+    unsafe { header.cast::<DropEntry<T>>().as_ref().value.drop_in_place() }
+}
+
+/// A bump allocator that values can be emplaced into.
+///
+/// Emplaced values live as long as the arena, and their destructors are run (in reverse order)
+/// when the arena is dropped. Since the memory of an emplaced value is never reused before then,
+/// values can also be pinned with [`emplace_pinned`](Arena::emplace_pinned).
+///
+/// `&Arena` also implements [`Allocator`], so it can be used with [`new_boxed_in`](crate::new_boxed_in)
+/// or [`Builder::with_alloc`](crate::Builder::with_alloc). Deallocating only frees memory if it was
+/// the most recent allocation.
+///
+/// The lifetime `'t` bounds the lifetime of the emplaced values, which must still be valid when the
+/// arena is dropped.
+pub struct Arena<'t, A: Allocator = Global> {
+    alloc: A,
+    /// The most recently allocated chunk, which `ptr` and `end` point into.
+    chunk: Cell<Option<NonNull<ChunkHeader>>>,
+    ptr: Cell<NonNull<u8>>,
+    end: Cell<NonNull<u8>>,
+    /// The most recently registered destructor.
+    drops: Cell<Option<NonNull<DropHeader>>>,
+    _marker: PhantomData<fn(&'t ()) -> &'t ()>,
+}
+
+/// Rolls the bump pointer back to where it was before emplacing, if emplacing fails or panics
+/// and nothing else was allocated in the meantime.
+struct RollbackOnDrop<'a, 't, A: Allocator> {
+    arena: &'a Arena<'t, A>,
+    chunk: Option<NonNull<ChunkHeader>>,
+    ptr: NonNull<u8>,
+    /// The bump pointer after the last allocation made for the emplaced value.
+    top: NonNull<u8>,
+}
+
+impl<A: Allocator> Drop for RollbackOnDrop<'_, '_, A> {
+    fn drop(&mut self) {
+        let arena = self.arena;
+        if arena.ptr.get() != self.top {
+            return;
+        }
+        match arena.chunk.get() {
+            chunk if chunk == self.chunk => arena.ptr.set(self.ptr),
+            // A new chunk was started for this value, so nothing else is allocated in it.
+            // SAFETY: the header is at the start of the chunk, followed by its free space
+            Some(chunk) => arena.ptr.set(unsafe { chunk.add(1).cast() }),
+            None => {}
+        }
+    }
+}
+
+impl<'t> Arena<'t> {
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<'t> Default for Arena<'t> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Each emplaced value gets fresh memory, so the returned references never alias.
+#[allow(clippy::mut_from_ref)]
+impl<'t, A: Allocator> Arena<'t, A> {
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            alloc,
+            chunk: Cell::new(None),
+            ptr: Cell::new(NonNull::dangling()),
+            end: Cell::new(NonNull::dangling()),
+            drops: Cell::new(None),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Allocate a block of memory for `layout`, bumping the pointer in the current chunk or
+    /// starting a new chunk if it is full.
+    fn allocate_layout(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        if layout.size() == 0 {
+            return Ok(layout.dangling());
+        }
+        let ptr = self.ptr.get();
+        let end = self.end.get().addr().get();
+        let start = ptr.addr().get().checked_next_multiple_of(layout.align());
+        match start.and_then(|start| Some((start, start.checked_add(layout.size())?))) {
+            Some((start, next)) if next <= end => {
+                // SAFETY: `start` and `next` lie within the current chunk, so they are non-zero
+                unsafe {
+                    self.ptr.set(ptr.with_addr(NonZero::new_unchecked(next)));
+                    Ok(ptr.with_addr(NonZero::new_unchecked(start)))
+                }
+            }
+            _ => self.allocate_chunk(layout),
+        }
+    }
+
+    #[cold]
+    fn allocate_chunk(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        let prev_size = self
+            .chunk
+            .get()
+            // SAFETY: `chunk` points to a live chunk header
+            .map_or(0, |chunk| unsafe { chunk.as_ref().layout.size() });
+        let (needed, offset) = Layout::new::<ChunkHeader>()
+            .extend(layout)
+            .map_err(|_| AllocError)?;
+        let size = needed
+            .size()
+            .max(prev_size.saturating_mul(2))
+            .max(MIN_CHUNK_SIZE);
+        let chunk_layout = Layout::from_size_align(size, needed.align()).map_err(|_| AllocError)?;
+
+        let chunk = self.alloc.allocate(chunk_layout)?.cast::<ChunkHeader>();
+        // SAFETY: `chunk` was just allocated with a layout that fits a `ChunkHeader` followed by `layout`
+        unsafe {
+            chunk.write(ChunkHeader {
+                prev: self.chunk.get(),
+                layout: chunk_layout,
+            });
+            let base = chunk.cast::<u8>();
+            let ptr = base.add(offset);
+            self.chunk.set(Some(chunk));
+            self.ptr.set(ptr.add(layout.size()));
+            self.end.set(base.add(size));
+            Ok(ptr)
+        }
+    }
+
+    /// # Safety
+    ///
+    /// Either `init` implements `Init<T>`, or the returned reference is immediately pinned.
+    unsafe fn emplace_impl<T: MetaSized + 't, Error>(
+        &self,
+        init: impl PinInit<T, Error>,
+    ) -> Result<&mut T, AllocOrInit<Error>> {
+        let metadata = init.metadata();
+        let layout = layout_for_metadata::<T>(metadata)?;
+
+        let mut guard = RollbackOnDrop {
+            arena: self,
+            chunk: self.chunk.get(),
+            ptr: self.ptr.get(),
+            top: self.ptr.get(),
+        };
+        let entry = if core::mem::needs_drop::<T>() {
+            let entry_layout = Layout::new::<DropEntry<T>>();
+            let entry = self
+                .allocate_layout(entry_layout)
+                .map_err(|_| AllocOrInit::Alloc(entry_layout))?;
+            guard.top = self.ptr.get();
+            Some(entry)
+        } else {
+            None
+        };
+        let ptr = self
+            .allocate_layout(layout)
+            .map_err(|_| AllocOrInit::Alloc(layout))?;
+        guard.top = self.ptr.get();
+
+        let value = core::ptr::from_raw_parts_mut::<T>(ptr.as_ptr(), metadata);
+        // SAFETY: `value` was just allocated with the layout of `T`, and the caller upholds the rest
+        unsafe { init.init(value, ()) }.map_err(AllocOrInit::Init)?;
+        core::mem::forget(guard);
+
+        if let Some(entry) = entry {
+            let entry = entry.cast::<DropEntry<T>>();
+            // SAFETY: `entry` was allocated with the layout of `DropEntry<T>`
+            unsafe {
+                entry.write(DropEntry {
+                    header: DropHeader {
+                        prev: self.drops.get(),
+                        drop: drop_entry::<T>,
+                    },
+                    value,
+                });
+            }
+            self.drops.set(Some(entry.cast()));
+        }
+        // SAFETY: `value` was just initialized, and its memory is not reused until the arena is dropped
+        Ok(unsafe { &mut *value })
+    }
+
+    /// Emplace a value in the arena.
+    ///
+    /// If `init` fails or panics, the arena's bump pointer is rolled back.
+    pub fn try_emplace<T: MetaSized + 't, Error>(
+        &self,
+        init: impl Init<T, Error>,
+    ) -> Result<&mut T, Error> {
+        self.checked_emplace(init)
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+    /// Emplace a value in the arena.
+    pub fn emplace<T: MetaSized + 't>(&self, init: impl Init<T>) -> &mut T {
+        self.try_emplace(init).unwrap_or_else(|e| match e {})
+    }
+    /// Like [`try_emplace`](Arena::try_emplace), but surfaces allocation failure instead of aborting.
+    pub fn checked_emplace<T: MetaSized + 't, Error>(
+        &self,
+        init: impl Init<T, Error>,
+    ) -> Result<&mut T, AllocOrInit<Error>> {
+        // SAFETY: `init` implements `Init<T>`
+        unsafe { self.emplace_impl(init) }
+    }
+
+    /// Pin-initialize a value in the arena.
+    ///
+    /// If `init` fails or panics, the arena's bump pointer is rolled back.
+    pub fn try_emplace_pinned<T: MetaSized + 't, Error>(
+        &self,
+        init: impl PinInit<T, Error>,
+    ) -> Result<Pin<&mut T>, Error> {
+        self.checked_emplace_pinned(init)
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+    /// Pin-initialize a value in the arena.
+    pub fn emplace_pinned<T: MetaSized + 't>(&self, init: impl PinInit<T>) -> Pin<&mut T> {
+        self.try_emplace_pinned(init).unwrap_or_else(|e| match e {})
+    }
+    /// Like [`try_emplace_pinned`](Arena::try_emplace_pinned), but surfaces allocation failure instead of aborting.
+    pub fn checked_emplace_pinned<T: MetaSized + 't, Error>(
+        &self,
+        init: impl PinInit<T, Error>,
+    ) -> Result<Pin<&mut T>, AllocOrInit<Error>> {
+        // SAFETY: the reference is immediately pinned. The value's memory is not reused before its
+        // destructor runs when the arena is dropped (or never, if the arena is leaked).
+        unsafe {
+            self.emplace_impl(init)
+                .map(|value| Pin::new_unchecked(value))
+        }
+    }
+}
+
+impl<'t, A: Allocator> Drop for Arena<'t, A> {
+    fn drop(&mut self) {
+        while let Some(entry) = self.drops.take() {
+            // SAFETY: registered entries point to initialized values that are not used after this
+            unsafe {
+                self.drops.set(entry.as_ref().prev);
+                (entry.as_ref().drop)(entry);
+            }
+        }
+        while let Some(chunk) = self.chunk.take() {
+            // SAFETY: `chunk` points to a live chunk header, and the chunk was allocated by `self.alloc`
+            unsafe {
+                let ChunkHeader { prev, layout } = chunk.read();
+                self.chunk.set(prev);
+                self.alloc.deallocate(chunk.cast(), layout);
+            }
+        }
+    }
+}
+
+unsafe impl<'t, A: Allocator> Allocator for &Arena<'t, A> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = self.allocate_layout(layout)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // Only the most recent allocation can be freed.
+        if ptr.addr().get().wrapping_add(layout.size()) == self.ptr.get().addr().get() {
+            self.ptr.set(ptr);
+        }
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let addr = ptr.addr().get();
+        // Grow in place if this is the most recent allocation and the chunk has room.
+        if old_layout.size() > 0
+            && addr + old_layout.size() == self.ptr.get().addr().get()
+            && addr.is_multiple_of(new_layout.align())
+            && new_layout.size() <= self.end.get().addr().get() - addr
+        {
+            // SAFETY: `addr + new_layout.size()` lies within the current chunk, so it is non-zero
+            self.ptr
+                .set(ptr.with_addr(unsafe { NonZero::new_unchecked(addr + new_layout.size()) }));
+            return Ok(NonNull::slice_from_raw_parts(ptr, new_layout.size()));
+        }
+        let new_ptr = self.allocate_layout(new_layout)?;
+        // SAFETY: the caller ensures `ptr` is valid for `old_layout.size()` bytes, and the new
+        // block is at least that large and does not overlap the old one
+        unsafe {
+            core::ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), old_layout.size());
+        }
+        Ok(NonNull::slice_from_raw_parts(new_ptr, new_layout.size()))
+    }
+}
@@ -0,0 +1,69 @@
+use core::{mem::ManuallyDrop, ptr};
+
+use alloc::{alloc::Allocator, collections::BinaryHeap, vec::Vec};
+
+use crate::{Init, VecExt};
+
+pub trait BinaryHeapExt {
+    type Item;
+
+    /// Emplace an element into the heap, then sift it up to its position.
+    ///
+    /// If `init` fails or panics, the heap is left unchanged (though it may have grown its capacity).
+    fn try_push_emplace<Error>(&mut self, init: impl Init<Self::Item, Error>) -> Result<(), Error>;
+    /// Emplace an element into the heap, then sift it up to its position.
+    fn push_emplace(&mut self, init: impl Init<Self::Item>);
+}
+
+/// Puts the heap's data back into the heap, even if emplacing or sifting panics.
+struct RestoreOnDrop<'a, T: Ord, A: Allocator> {
+    heap: &'a mut BinaryHeap<T, A>,
+    data: ManuallyDrop<Vec<T, A>>,
+}
+
+impl<T: Ord, A: Allocator> Drop for RestoreOnDrop<'_, T, A> {
+    fn drop(&mut self) {
+        // SAFETY: `data` is not used after this.
+        // `data` is a max-heap, except if `T`'s `Ord` impl panicked while sifting, which can
+        // leave it out of order just like `BinaryHeap::push` would, but not unsound.
+        unsafe {
+            let data = ManuallyDrop::take(&mut self.data);
+            ptr::write(self.heap, BinaryHeap::from_raw_vec(data));
+        }
+    }
+}
+
+/// Moves the last element of `data` up until its parent is not less than it.
+fn sift_up<T: Ord>(data: &mut [T]) {
+    let Some(mut idx) = data.len().checked_sub(1) else {
+        return;
+    };
+    while idx > 0 {
+        let parent = (idx - 1) / 2;
+        if data[idx] <= data[parent] {
+            break;
+        }
+        data.swap(idx, parent);
+        idx = parent;
+    }
+}
+
+impl<T: Ord, A: Allocator> BinaryHeapExt for BinaryHeap<T, A> {
+    type Item = T;
+
+    fn try_push_emplace<Error>(&mut self, init: impl Init<T, Error>) -> Result<(), Error> {
+        // SAFETY: `guard` writes the data back into `self` when it is dropped
+        let data = unsafe { ptr::read(self) }.into_vec();
+        let mut guard = RestoreOnDrop {
+            heap: self,
+            data: ManuallyDrop::new(data),
+        };
+        guard.data.try_push_emplace(init)?;
+        sift_up(&mut guard.data);
+        Ok(())
+    }
+
+    fn push_emplace(&mut self, init: impl Init<T>) {
+        self.try_push_emplace(init).unwrap_or_else(|e| match e {});
+    }
+}
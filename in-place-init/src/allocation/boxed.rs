@@ -1,58 +1,67 @@
-use core::{marker::MetaSized, pin::Pin, ptr::NonNull};
+use core::{alloc::Layout, marker::MetaSized, mem::MaybeUninit, pin::Pin, ptr::NonNull};
 
 use alloc::{
     alloc::{Allocator, Global},
     boxed::Box,
 };
 
-use crate::{Init, PinInit};
+use crate::{Init, InitFromIterError, PinInit};
 
-/// # Safety
-///
-/// Either `init` implements `Init<T, Extra>`, or the returned `Box` is immediately pinned.
-pub(super) unsafe fn new_impl<T: MetaSized, Error, A: Allocator, Extra>(
-    init: impl PinInit<T, Error, Extra>,
-    alloc: A,
-    extra: Extra,
-) -> Result<Box<T, A>, Error> {
-    use core::alloc::Layout;
-    let metadata = init.metadata();
-    // SAFETY: this is unsound, size could overflow
-    // FIXME: should use checked_layout_for_meta if/when that's a thing
-    let layout = unsafe {
-        Layout::for_value_raw::<T>(core::ptr::from_raw_parts(core::ptr::null::<()>(), metadata))
-    };
+use super::{error::AllocOrInit, layout::layout_for_metadata};
 
-    let ptr = if layout.size() == 0 {
-        layout.dangling()
+fn allocate_or_dangling<A: Allocator, Error>(
+    layout: Layout,
+    alloc: &A,
+) -> Result<NonNull<u8>, AllocOrInit<Error>> {
+    if layout.size() == 0 {
+        Ok(layout.dangling())
     } else {
         match alloc.allocate(layout) {
-            Ok(ptr) => ptr.cast(),
-            Err(_) => alloc::alloc::handle_alloc_error(layout),
+            Ok(ptr) => Ok(ptr.cast()),
+            Err(_) => Err(AllocOrInit::Alloc(layout)),
         }
-    };
-
-    struct DeallocOnDrop<'a, A: Allocator> {
-        ptr: NonNull<u8>,
-        layout: Layout,
-        alloc: &'a A,
     }
-    impl<'a, A: Allocator> Drop for DeallocOnDrop<'a, A> {
-        fn drop(&mut self) {
+}
+
+struct DeallocOnDrop<'a, A: Allocator> {
+    ptr: NonNull<u8>,
+    layout: Layout,
+    alloc: &'a A,
+}
+impl<'a, A: Allocator> Drop for DeallocOnDrop<'a, A> {
+    fn drop(&mut self) {
+        if self.layout.size() > 0 {
             unsafe {
                 self.alloc.deallocate(self.ptr, self.layout);
             }
         }
     }
+}
+
+/// Initialize a value in a memory block, and return it as a `Box`.
+/// The memory block is deallocated if `init` fails or panics.
+///
+/// # Safety
+///
+/// * `ptr` must be dangling if `layout.size() == 0`, and must be currently allocated by `alloc`
+///   with `layout` otherwise.
+/// * `layout` must be the layout of a `T` with metadata `init.metadata()`.
+/// * Either `init` implements `Init<T, Extra>`, or the returned `Box` is immediately pinned.
+unsafe fn init_in_block<T: MetaSized, Error, A: Allocator, Extra>(
+    ptr: NonNull<u8>,
+    layout: Layout,
+    init: impl PinInit<T, Error, Extra>,
+    alloc: A,
+    extra: Extra,
+) -> Result<Box<T, A>, Error> {
     let guard = DeallocOnDrop {
         ptr,
         layout,
         alloc: &alloc,
     };
 
-    let ptr = core::ptr::from_raw_parts_mut(ptr.as_ptr(), metadata);
+    let ptr = core::ptr::from_raw_parts_mut(ptr.as_ptr(), init.metadata());
 
-    // TODO: deallocate on panic
     match unsafe { init.init(ptr, extra) } {
         Ok(()) => {
             core::mem::forget(guard);
@@ -65,19 +74,35 @@ pub(super) unsafe fn new_impl<T: MetaSized, Error, A: Allocator, Extra>(
     }
 }
 
+/// # Safety
+///
+/// Either `init` implements `Init<T, Extra>`, or the returned `Box` is immediately pinned.
+pub(super) unsafe fn new_impl<T: MetaSized, Error, A: Allocator, Extra>(
+    init: impl PinInit<T, Error, Extra>,
+    alloc: A,
+    extra: Extra,
+) -> Result<Box<T, A>, AllocOrInit<Error>> {
+    let layout = layout_for_metadata::<T>(init.metadata())?;
+    let ptr = allocate_or_dangling(layout, &alloc)?;
+    // SAFETY: `ptr` was just allocated with `layout`, and the caller upholds the rest
+    unsafe { init_in_block(ptr, layout, init, alloc, extra) }.map_err(AllocOrInit::Init)
+}
+
 pub fn try_new_boxed_in<T: MetaSized, Error, A: Allocator>(
     init: impl Init<T, Error>,
     alloc: A,
 ) -> Result<Box<T, A>, Error> {
     // Safety: `init` implements `Init<T>`
-    unsafe { new_impl(init, alloc, ()) }
+    unsafe { new_impl(init, alloc, ()) }.map_err(AllocOrInit::handle_alloc_error)
 }
 pub fn try_new_pinned_in<T: MetaSized, Error, A: Allocator + 'static>(
     init: impl PinInit<T, Error>,
     alloc: A,
 ) -> Result<Pin<Box<T, A>>, Error> {
     // Safety: the box is immediately pinned
-    unsafe { new_impl(init, alloc, ()).map(Box::into_pin) }
+    unsafe { new_impl(init, alloc, ()) }
+        .map(Box::into_pin)
+        .map_err(AllocOrInit::handle_alloc_error)
 }
 pub fn new_boxed_in<T: MetaSized, A: Allocator>(init: impl Init<T>, alloc: A) -> Box<T, A> {
     try_new_boxed_in(init, alloc).unwrap_or_else(|e| match e {})
@@ -92,6 +117,22 @@ pub fn new_pinned_in<T: MetaSized, A: Allocator + 'static>(
 pub fn try_new_boxed<T: MetaSized, Error>(init: impl Init<T, Error>) -> Result<Box<T>, Error> {
     try_new_boxed_in(init, Global)
 }
+/// Collect an iterator into a new `Box<[T]>`, without an intermediate `Vec`.
+pub fn try_new_boxed_from_iter<T>(
+    iter: impl IntoIterator<IntoIter: ExactSizeIterator<Item = T>>,
+) -> Result<Box<[T]>, InitFromIterError> {
+    try_new_boxed(crate::from_iter(iter))
+}
+/// Collect an iterator into a new `Box<[T]>`, without an intermediate `Vec`.
+///
+/// # Panics
+///
+/// Panics if the iterator does not yield exactly as many items as its `len()`.
+pub fn new_boxed_from_iter<T>(
+    iter: impl IntoIterator<IntoIter: ExactSizeIterator<Item = T>>,
+) -> Box<[T]> {
+    try_new_boxed_from_iter(iter).unwrap_or_else(|e| panic!("{e}"))
+}
 pub fn try_new_pinned<T: MetaSized, Error>(
     init: impl PinInit<T, Error>,
 ) -> Result<Pin<Box<T>>, Error> {
@@ -103,3 +144,149 @@ pub fn new_boxed<T: MetaSized>(init: impl Init<T>) -> Box<T> {
 pub fn new_pinned<T: MetaSized>(init: impl PinInit<T>) -> Pin<Box<T>> {
     try_new_pinned_in(init, Global).unwrap_or_else(|e| match e {})
 }
+
+/// Like [`try_new_boxed_in`], but surfaces allocation failure instead of aborting.
+pub fn checked_new_boxed_in<T: MetaSized, Error, A: Allocator>(
+    init: impl Init<T, Error>,
+    alloc: A,
+) -> Result<Box<T, A>, AllocOrInit<Error>> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { new_impl(init, alloc, ()) }
+}
+/// Like [`try_new_pinned_in`], but surfaces allocation failure instead of aborting.
+pub fn checked_new_pinned_in<T: MetaSized, Error, A: Allocator + 'static>(
+    init: impl PinInit<T, Error>,
+    alloc: A,
+) -> Result<Pin<Box<T, A>>, AllocOrInit<Error>> {
+    // Safety: the box is immediately pinned
+    unsafe { new_impl(init, alloc, ()).map(Box::into_pin) }
+}
+/// Like [`try_new_boxed`], but surfaces allocation failure instead of aborting.
+pub fn checked_new_boxed<T: MetaSized, Error>(
+    init: impl Init<T, Error>,
+) -> Result<Box<T>, AllocOrInit<Error>> {
+    checked_new_boxed_in(init, Global)
+}
+/// Like [`try_new_pinned`], but surfaces allocation failure instead of aborting.
+pub fn checked_new_pinned<T: MetaSized, Error>(
+    init: impl PinInit<T, Error>,
+) -> Result<Pin<Box<T>>, AllocOrInit<Error>> {
+    checked_new_pinned_in(init, Global)
+}
+
+/// Initialize the value in a `Box<MaybeUninit<T>>`.
+///
+/// If `init` fails or panics, the box is deallocated.
+pub fn try_init_boxed<T, Error, A: Allocator>(
+    mut boxed: Box<MaybeUninit<T>, A>,
+    init: impl Init<T, Error>,
+) -> Result<Box<T, A>, Error> {
+    // SAFETY: the box is uniquely owned, so it is valid for writes
+    unsafe { init.init(boxed.as_mut_ptr(), ())? };
+    // SAFETY: we just initialized the box
+    Ok(unsafe { boxed.assume_init() })
+}
+/// Initialize the value in a `Box<MaybeUninit<T>>`.
+pub fn init_boxed<T, A: Allocator>(boxed: Box<MaybeUninit<T>, A>, init: impl Init<T>) -> Box<T, A> {
+    try_init_boxed(boxed, init).unwrap_or_else(|e| match e {})
+}
+
+/// Initialize the elements in a `Box<[MaybeUninit<T>]>`.
+///
+/// If `init` fails or panics, the box is deallocated.
+///
+/// # Panics
+///
+/// Panics if `init`'s length does not match the length of the slice.
+pub fn try_init_boxed_slice<T, Error, A: Allocator>(
+    mut boxed: Box<[MaybeUninit<T>], A>,
+    init: impl Init<[T], Error>,
+) -> Result<Box<[T], A>, Error> {
+    crate::try_initialize_slice(&mut boxed, init)?;
+    // SAFETY: we just initialized the elements
+    Ok(unsafe { boxed.assume_init() })
+}
+/// Initialize the elements in a `Box<[MaybeUninit<T>]>`.
+///
+/// # Panics
+///
+/// Panics if `init`'s length does not match the length of the slice.
+pub fn init_boxed_slice<T, A: Allocator>(
+    boxed: Box<[MaybeUninit<T>], A>,
+    init: impl Init<[T]>,
+) -> Box<[T], A> {
+    try_init_boxed_slice(boxed, init).unwrap_or_else(|e| match e {})
+}
+
+pub trait BoxExt: Sized {
+    type Target: MetaSized;
+
+    /// Drop the boxed value, and emplace a new value in its place.
+    ///
+    /// The allocation is reused if the new value has the same layout as the old one,
+    /// and is reallocated otherwise.
+    ///
+    /// If `init` fails or panics, the allocation is freed.
+    fn try_replace_emplace<Error>(
+        self,
+        init: impl Init<Self::Target, Error>,
+    ) -> Result<Self, Error>;
+    /// Drop the boxed value, and emplace a new value in its place.
+    ///
+    /// The allocation is reused if the new value has the same layout as the old one,
+    /// and is reallocated otherwise.
+    fn replace_emplace(self, init: impl Init<Self::Target>) -> Self;
+    /// Like [`try_replace_emplace`](BoxExt::try_replace_emplace), but surfaces allocation
+    /// failure instead of aborting.
+    ///
+    /// If reallocating fails, the old value is dropped and the allocation is freed.
+    fn checked_replace_emplace<Error>(
+        self,
+        init: impl Init<Self::Target, Error>,
+    ) -> Result<Self, AllocOrInit<Error>>;
+}
+
+impl<T: MetaSized, A: Allocator> BoxExt for Box<T, A> {
+    type Target = T;
+
+    fn try_replace_emplace<Error>(self, init: impl Init<T, Error>) -> Result<Self, Error> {
+        self.checked_replace_emplace(init)
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    fn replace_emplace(self, init: impl Init<T>) -> Self {
+        self.try_replace_emplace(init)
+            .unwrap_or_else(|e| match e {})
+    }
+
+    fn checked_replace_emplace<Error>(
+        self,
+        init: impl Init<T, Error>,
+    ) -> Result<Self, AllocOrInit<Error>> {
+        let new_layout = layout_for_metadata::<T>(init.metadata())?;
+        let (ptr, alloc) = Box::into_raw_with_allocator(self);
+        // SAFETY: `ptr` came from a `Box`, so it points to a valid `T`
+        let old_layout = unsafe { Layout::for_value_raw(ptr) };
+        // SAFETY: `ptr` came from a `Box`, so it is non-null
+        let old_ptr = unsafe { NonNull::new_unchecked(ptr.cast::<u8>()) };
+
+        let guard = DeallocOnDrop {
+            ptr: old_ptr,
+            layout: old_layout,
+            alloc: &alloc,
+        };
+        // SAFETY: the value is not used after this, and `guard` frees the allocation if this panics
+        unsafe { ptr.drop_in_place() };
+
+        let ptr = if new_layout == old_layout {
+            core::mem::forget(guard);
+            old_ptr
+        } else {
+            drop(guard);
+            allocate_or_dangling(new_layout, &alloc)?
+        };
+        // SAFETY: `ptr` is either the old block (with the same layout) or was just allocated with `new_layout`,
+        // and `init` implements `Init<T>`
+        unsafe { init_in_block(ptr, new_layout, init, alloc, ()) }.map_err(AllocOrInit::Init)
+    }
+}
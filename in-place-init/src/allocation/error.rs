@@ -0,0 +1,69 @@
+use core::{
+    alloc::{Layout, LayoutError},
+    fmt,
+};
+
+use alloc::collections::{TryReserveError, TryReserveErrorKind};
+
+/// The error returned by the `checked_*` allocation functions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllocOrInit<E> {
+    /// The layout of the value could not be computed, because its size would overflow `isize`.
+    Layout(LayoutError),
+    /// The allocator failed to allocate memory for this layout.
+    Alloc(Layout),
+    /// The initializer failed.
+    Init(E),
+}
+
+impl<E> AllocOrInit<E> {
+    /// Returns the initializer error, handling allocation failures the way the non-`checked_*`
+    /// functions do: panicking on layout overflow, and calling
+    /// [`handle_alloc_error`](alloc::alloc::handle_alloc_error) on allocation failure.
+    pub(crate) fn handle_alloc_error(self) -> E {
+        match self {
+            AllocOrInit::Layout(_) => panic!("capacity overflow"),
+            AllocOrInit::Alloc(layout) => alloc::alloc::handle_alloc_error(layout),
+            AllocOrInit::Init(err) => err,
+        }
+    }
+}
+
+impl<E> From<LayoutError> for AllocOrInit<E> {
+    fn from(err: LayoutError) -> Self {
+        AllocOrInit::Layout(err)
+    }
+}
+
+impl<E> From<TryReserveError> for AllocOrInit<E> {
+    fn from(err: TryReserveError) -> Self {
+        match err.kind() {
+            TryReserveErrorKind::CapacityOverflow => {
+                AllocOrInit::Layout(super::layout::overflow_error())
+            }
+            TryReserveErrorKind::AllocError { layout, .. } => AllocOrInit::Alloc(layout),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for AllocOrInit<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllocOrInit::Layout(err) => err.fmt(f),
+            AllocOrInit::Alloc(layout) => {
+                write!(f, "memory allocation of {} bytes failed", layout.size())
+            }
+            AllocOrInit::Init(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for AllocOrInit<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            AllocOrInit::Layout(err) => Some(err),
+            AllocOrInit::Alloc(_) => None,
+            AllocOrInit::Init(err) => Some(err),
+        }
+    }
+}
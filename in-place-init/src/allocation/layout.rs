@@ -0,0 +1,73 @@
+use core::{
+    alloc::{Layout, LayoutError},
+    marker::MetaSized,
+    ptr::Pointee,
+};
+
+/// Computes the layout of a `T` with the given pointer metadata, or returns an error if its size
+/// would overflow `isize`.
+///
+/// This is a checked version of [`Layout::for_value_raw`] that is safe to use with the metadata of
+/// any initializer, e.g. a slice initializer with a huge length.
+pub fn layout_for_metadata<T: MetaSized>(
+    metadata: <T as Pointee>::Metadata,
+) -> Result<Layout, LayoutError> {
+    <<T as Pointee>::Metadata as LayoutForMetadata<T>>::layout_for_metadata(metadata)
+}
+
+/// Dispatches on the kind of pointer metadata.
+trait LayoutForMetadata<T: MetaSized> {
+    fn layout_for_metadata(metadata: Self) -> Result<Layout, LayoutError>;
+}
+
+impl<M, T: MetaSized + Pointee<Metadata = M>> LayoutForMetadata<T> for M {
+    /// Sized types (`()` metadata) and trait objects (`DynMetadata` pointing to a vtable)
+    /// always have a valid layout.
+    default fn layout_for_metadata(metadata: M) -> Result<Layout, LayoutError> {
+        // SAFETY: the metadata is not a slice length, so it describes a valid layout
+        Ok(unsafe {
+            Layout::for_value_raw::<T>(core::ptr::from_raw_parts(core::ptr::null::<()>(), metadata))
+        })
+    }
+}
+
+impl<T: MetaSized + Pointee<Metadata = usize>> LayoutForMetadata<T> for usize {
+    /// Types with a slice tail have a layout of `round_up(offset + len * elem_size, align)`,
+    /// which can overflow.
+    fn layout_for_metadata(len: usize) -> Result<Layout, LayoutError> {
+        // SAFETY: the size must fit in `isize`, which the caller ensures
+        let layout_with_len = |len: usize| unsafe {
+            Layout::for_value_raw::<T>(core::ptr::from_raw_parts(core::ptr::null::<()>(), len))
+        };
+        // NOTE: this assumes the statically sized prefix plus one tail element fit in `isize`,
+        // which holds for all types rustc accepts in practice.
+        let empty = layout_with_len(0);
+        if len == 0 {
+            return Ok(empty);
+        }
+        let one = layout_with_len(1);
+        let align = empty.align();
+
+        // `one.size()` is an upper bound on the element size, so this bounds the size with `align` elements.
+        one.size()
+            .checked_mul(align)
+            .and_then(|size| size.checked_add(empty.size()))
+            .and_then(|size| Layout::from_size_align(size, align).ok())
+            .ok_or_else(overflow_error)?;
+        // Adding `align` elements adds exactly `align * elem_size` bytes, since that is a multiple of `align`.
+        let elem_size = (layout_with_len(align).size() - empty.size()) / align;
+
+        // `empty.size() + len * elem_size`, padded, is an upper bound on the actual size.
+        elem_size
+            .checked_mul(len)
+            .and_then(|size| size.checked_add(empty.size()))
+            .and_then(|size| Layout::from_size_align(size, align).ok())
+            .ok_or_else(overflow_error)?;
+        Ok(layout_with_len(len))
+    }
+}
+
+/// A `LayoutError` for sizes that overflow `isize`.
+pub(crate) fn overflow_error() -> LayoutError {
+    Layout::array::<u8>(usize::MAX).unwrap_err()
+}
@@ -4,15 +4,25 @@ use alloc::{
     alloc::{Allocator, Global},
     boxed::Box,
     rc::Rc,
+    sync::Arc,
     vec::Vec,
 };
 
 use crate::{Init, PinInit};
 
+use error::AllocOrInit;
+
+pub(crate) mod arc;
+pub(crate) mod arena;
+pub(crate) mod binary_heap;
 pub(crate) mod boxed;
+pub(crate) mod error;
+pub(crate) mod layout;
 pub(crate) mod rc;
 pub(crate) mod string;
 pub(crate) mod vec;
+#[cfg(feature = "assume-vec-deque-layout")]
+pub(crate) mod vec_deque;
 
 pub struct Builder<I, A: Allocator, Extra> {
     init: I,
@@ -58,6 +68,15 @@ impl<I, A: Allocator, Extra> Builder<I, A, Extra> {
     }
 
     pub fn try_build_box<T: MetaSized, Error>(self) -> Result<Box<T, A>, Error>
+    where
+        I: Init<T, Error, Extra>,
+    {
+        self.checked_build_box()
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_box`](Self::try_build_box), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_box<T: MetaSized, Error>(self) -> Result<Box<T, A>, AllocOrInit<Error>>
     where
         I: Init<T, Error, Extra>,
     {
@@ -66,6 +85,18 @@ impl<I, A: Allocator, Extra> Builder<I, A, Extra> {
     }
 
     pub fn try_build_pinned_box<T: MetaSized, Error>(self) -> Result<Pin<Box<T, A>>, Error>
+    where
+        I: PinInit<T, Error, Extra>,
+        A: 'static,
+    {
+        self.checked_build_pinned_box()
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_pinned_box`](Self::try_build_pinned_box), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_pinned_box<T: MetaSized, Error>(
+        self,
+    ) -> Result<Pin<Box<T, A>>, AllocOrInit<Error>>
     where
         I: PinInit<T, Error, Extra>,
         A: 'static,
@@ -96,6 +127,14 @@ impl<I, A: Allocator, Extra> Builder<I, A, Extra> {
         self.try_build_box().map(Vec::from)
     }
 
+    /// Like [`try_build_vec`](Self::try_build_vec), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_vec<T, Error>(self) -> Result<Vec<T, A>, AllocOrInit<Error>>
+    where
+        I: Init<[T], Error, Extra>,
+    {
+        self.checked_build_box().map(Vec::from)
+    }
+
     pub fn build_vec<T>(self) -> Vec<T, A>
     where
         I: Init<[T], !, Extra>,
@@ -103,40 +142,57 @@ impl<I, A: Allocator, Extra> Builder<I, A, Extra> {
         Vec::from(self.try_build_box().unwrap_or_else(|e| match e {}))
     }
 
-    pub fn try_build_rc<T: MetaSized, Error>(self) -> Result<Rc<T, A>, Error>
+    pub fn try_build_rc<T: MetaSized + rc::RcDst, Error>(self) -> Result<Rc<T, A>, Error>
+    where
+        I: Init<T, Error, Extra>,
+    {
+        self.checked_build_rc()
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_rc`](Self::try_build_rc), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_rc<T: MetaSized + rc::RcDst, Error>(
+        self,
+    ) -> Result<Rc<T, A>, AllocOrInit<Error>>
     where
         I: Init<T, Error, Extra>,
     {
         // SAFETY: `I` implements `Init<T, Extra>`
-        unsafe {
-            rc::rc_new_base_impl::<T, Error, A, Extra, rc::NonWeakExtra>(
-                self.init, self.alloc, self.extra,
-            )
-        }
+        unsafe { T::rc_new_impl(self.init, self.alloc, self.extra) }
     }
 
-    pub fn try_build_pinned_rc<T: MetaSized, Error>(self) -> Result<Pin<Rc<T, A>>, Error>
+    pub fn try_build_pinned_rc<T: MetaSized + rc::RcDst, Error>(
+        self,
+    ) -> Result<Pin<Rc<T, A>>, Error>
+    where
+        I: PinInit<T, Error, Extra>,
+        A: 'static,
+    {
+        self.checked_build_pinned_rc()
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_pinned_rc`](Self::try_build_pinned_rc), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_pinned_rc<T: MetaSized + rc::RcDst, Error>(
+        self,
+    ) -> Result<Pin<Rc<T, A>>, AllocOrInit<Error>>
     where
         I: PinInit<T, Error, Extra>,
         A: 'static,
     {
         // Safety: the rc is immediately pinned
-        let rc = unsafe {
-            rc::rc_new_base_impl::<T, Error, A, Extra, rc::NonWeakExtra>(
-                self.init, self.alloc, self.extra,
-            )
-        }?;
+        let rc = unsafe { T::rc_new_impl(self.init, self.alloc, self.extra) }?;
         Ok(unsafe { Pin::new_unchecked(rc) })
     }
 
-    pub fn build_rc<T: MetaSized>(self) -> Rc<T, A>
+    pub fn build_rc<T: MetaSized + rc::RcDst>(self) -> Rc<T, A>
     where
         I: Init<T, !, Extra>,
     {
         self.try_build_rc().unwrap_or_else(|e| match e {})
     }
 
-    pub fn build_pinned_rc<T: MetaSized>(self) -> Pin<Rc<T, A>>
+    pub fn build_pinned_rc<T: MetaSized + rc::RcDst>(self) -> Pin<Rc<T, A>>
     where
         I: PinInit<T, !, Extra>,
         A: 'static,
@@ -144,39 +200,64 @@ impl<I, A: Allocator, Extra> Builder<I, A, Extra> {
         self.try_build_pinned_rc().unwrap_or_else(|e| match e {})
     }
 
-    pub fn try_build_cyclic_rc_with<T: MetaSized, Error>(self) -> Result<Rc<T, A>, Error>
+    pub fn try_build_cyclic_rc_with<T: MetaSized + rc::RcCyclicDst, Error>(
+        self,
+    ) -> Result<Rc<T, A>, Error>
+    where
+        I: Init<T, Error, (rc::Weak<T, A>, Extra)>,
+        A: Clone,
+    {
+        self.checked_build_cyclic_rc_with()
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_cyclic_rc_with`](Self::try_build_cyclic_rc_with), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_cyclic_rc_with<T: MetaSized + rc::RcCyclicDst, Error>(
+        self,
+    ) -> Result<Rc<T, A>, AllocOrInit<Error>>
     where
         I: Init<T, Error, (rc::Weak<T, A>, Extra)>,
         A: Clone,
     {
         // SAFETY: `I` implements `Init<T, _>`
-        unsafe {
-            rc::rc_new_base_impl::<T, Error, A, Extra, rc::WithWeakExtra>(
-                self.init, self.alloc, self.extra,
-            )
-        }
+        unsafe { T::rc_new_cyclic_impl(self.init, self.alloc, |weak| (weak.clone(), self.extra)) }
     }
 
     /// # Safety
     ///
     /// The `rc::Weak<T, A>`s passed to `init` must be treated as pinned.
-    pub unsafe fn try_build_pinned_cyclic_rc_with<T: MetaSized, Error>(
+    pub unsafe fn try_build_pinned_cyclic_rc_with<T: MetaSized + rc::RcCyclicDst, Error>(
         self,
     ) -> Result<Pin<Rc<T, A>>, Error>
+    where
+        I: PinInit<T, Error, (rc::Weak<T, A>, Extra)>,
+        A: Clone + 'static,
+    {
+        // SAFETY: discharged to caller
+        unsafe { self.checked_build_pinned_cyclic_rc_with() }
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_pinned_cyclic_rc_with`](Self::try_build_pinned_cyclic_rc_with), but surfaces allocation failure instead of aborting.
+    ///
+    /// # Safety
+    ///
+    /// The `rc::Weak<T, A>`s passed to `init` must be treated as pinned.
+    pub unsafe fn checked_build_pinned_cyclic_rc_with<T: MetaSized + rc::RcCyclicDst, Error>(
+        self,
+    ) -> Result<Pin<Rc<T, A>>, AllocOrInit<Error>>
     where
         I: PinInit<T, Error, (rc::Weak<T, A>, Extra)>,
         A: Clone + 'static,
     {
         // Safety: the rc is immediately pinned, the `Weak` requirement is discharged to the caller
         let rc = unsafe {
-            rc::rc_new_base_impl::<T, Error, A, Extra, rc::WithWeakExtra>(
-                self.init, self.alloc, self.extra,
-            )
+            T::rc_new_cyclic_impl(self.init, self.alloc, |weak| (weak.clone(), self.extra))
         }?;
         Ok(unsafe { Pin::new_unchecked(rc) })
     }
 
-    pub fn build_cyclic_rc_with<T: MetaSized>(self) -> Rc<T, A>
+    pub fn build_cyclic_rc_with<T: MetaSized + rc::RcCyclicDst>(self) -> Rc<T, A>
     where
         I: Init<T, !, (rc::Weak<T, A>, Extra)>,
         A: Clone,
@@ -188,7 +269,7 @@ impl<I, A: Allocator, Extra> Builder<I, A, Extra> {
     /// # Safety
     ///
     /// The `rc::Weak<T, A>`s passed to `init` must be treated as pinned.
-    pub unsafe fn build_pinned_cyclic_rc_with<T: MetaSized>(self) -> Pin<Rc<T, A>>
+    pub unsafe fn build_pinned_cyclic_rc_with<T: MetaSized + rc::RcCyclicDst>(self) -> Pin<Rc<T, A>>
     where
         I: PinInit<T, !, (rc::Weak<T, A>, Extra)>,
         A: Clone + 'static,
@@ -199,43 +280,294 @@ impl<I, A: Allocator, Extra> Builder<I, A, Extra> {
                 .unwrap_or_else(|e| match e {})
         }
     }
+
+    pub fn try_build_arc<T: MetaSized + arc::ArcDst, Error>(self) -> Result<Arc<T, A>, Error>
+    where
+        I: Init<T, Error, Extra>,
+    {
+        self.checked_build_arc()
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_arc`](Self::try_build_arc), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_arc<T: MetaSized + arc::ArcDst, Error>(
+        self,
+    ) -> Result<Arc<T, A>, AllocOrInit<Error>>
+    where
+        I: Init<T, Error, Extra>,
+    {
+        // SAFETY: `I` implements `Init<T, Extra>`
+        unsafe { T::arc_new_impl(self.init, self.alloc, self.extra) }
+    }
+
+    pub fn try_build_pinned_arc<T: MetaSized + arc::ArcDst, Error>(
+        self,
+    ) -> Result<Pin<Arc<T, A>>, Error>
+    where
+        I: PinInit<T, Error, Extra>,
+        A: 'static,
+    {
+        self.checked_build_pinned_arc()
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_pinned_arc`](Self::try_build_pinned_arc), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_pinned_arc<T: MetaSized + arc::ArcDst, Error>(
+        self,
+    ) -> Result<Pin<Arc<T, A>>, AllocOrInit<Error>>
+    where
+        I: PinInit<T, Error, Extra>,
+        A: 'static,
+    {
+        // Safety: the arc is immediately pinned
+        let arc = unsafe { T::arc_new_impl(self.init, self.alloc, self.extra) }?;
+        Ok(unsafe { Pin::new_unchecked(arc) })
+    }
+
+    pub fn build_arc<T: MetaSized + arc::ArcDst>(self) -> Arc<T, A>
+    where
+        I: Init<T, !, Extra>,
+    {
+        self.try_build_arc().unwrap_or_else(|e| match e {})
+    }
+
+    pub fn build_pinned_arc<T: MetaSized + arc::ArcDst>(self) -> Pin<Arc<T, A>>
+    where
+        I: PinInit<T, !, Extra>,
+        A: 'static,
+    {
+        self.try_build_pinned_arc().unwrap_or_else(|e| match e {})
+    }
+
+    pub fn try_build_cyclic_arc_with<T: MetaSized + arc::ArcCyclicDst, Error>(
+        self,
+    ) -> Result<Arc<T, A>, Error>
+    where
+        I: Init<T, Error, (arc::Weak<T, A>, Extra)>,
+        A: Clone,
+    {
+        self.checked_build_cyclic_arc_with()
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_cyclic_arc_with`](Self::try_build_cyclic_arc_with), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_cyclic_arc_with<T: MetaSized + arc::ArcCyclicDst, Error>(
+        self,
+    ) -> Result<Arc<T, A>, AllocOrInit<Error>>
+    where
+        I: Init<T, Error, (arc::Weak<T, A>, Extra)>,
+        A: Clone,
+    {
+        // SAFETY: `I` implements `Init<T, _>`
+        unsafe { T::arc_new_cyclic_impl(self.init, self.alloc, |weak| (weak.clone(), self.extra)) }
+    }
+
+    /// # Safety
+    ///
+    /// The `sync::Weak<T, A>`s passed to `init` must be treated as pinned.
+    pub unsafe fn try_build_pinned_cyclic_arc_with<T: MetaSized + arc::ArcCyclicDst, Error>(
+        self,
+    ) -> Result<Pin<Arc<T, A>>, Error>
+    where
+        I: PinInit<T, Error, (arc::Weak<T, A>, Extra)>,
+        A: Clone + 'static,
+    {
+        // SAFETY: discharged to caller
+        unsafe { self.checked_build_pinned_cyclic_arc_with() }
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_pinned_cyclic_arc_with`](Self::try_build_pinned_cyclic_arc_with), but surfaces allocation failure instead of aborting.
+    ///
+    /// # Safety
+    ///
+    /// The `sync::Weak<T, A>`s passed to `init` must be treated as pinned.
+    pub unsafe fn checked_build_pinned_cyclic_arc_with<T: MetaSized + arc::ArcCyclicDst, Error>(
+        self,
+    ) -> Result<Pin<Arc<T, A>>, AllocOrInit<Error>>
+    where
+        I: PinInit<T, Error, (arc::Weak<T, A>, Extra)>,
+        A: Clone + 'static,
+    {
+        // Safety: the arc is immediately pinned, the `Weak` requirement is discharged to the caller
+        let arc = unsafe {
+            T::arc_new_cyclic_impl(self.init, self.alloc, |weak| (weak.clone(), self.extra))
+        }?;
+        Ok(unsafe { Pin::new_unchecked(arc) })
+    }
+
+    pub fn build_cyclic_arc_with<T: MetaSized + arc::ArcCyclicDst>(self) -> Arc<T, A>
+    where
+        I: Init<T, !, (arc::Weak<T, A>, Extra)>,
+        A: Clone,
+    {
+        self.try_build_cyclic_arc_with()
+            .unwrap_or_else(|e| match e {})
+    }
+
+    /// # Safety
+    ///
+    /// The `sync::Weak<T, A>`s passed to `init` must be treated as pinned.
+    pub unsafe fn build_pinned_cyclic_arc_with<T: MetaSized + arc::ArcCyclicDst>(
+        self,
+    ) -> Pin<Arc<T, A>>
+    where
+        I: PinInit<T, !, (arc::Weak<T, A>, Extra)>,
+        A: Clone + 'static,
+    {
+        // SAFETY: discharged to caller
+        unsafe {
+            self.try_build_pinned_cyclic_arc_with()
+                .unwrap_or_else(|e| match e {})
+        }
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn try_build_unique_rc<T: MetaSized, Error>(self) -> Result<rc::UniqueRc<T, A>, Error>
+    where
+        I: Init<T, Error, Extra>,
+    {
+        // SAFETY: `I` implements `Init<T, Extra>`
+        unsafe { rc::unique_rc_new_layout_impl(self.init, self.alloc, |_| self.extra) }
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn build_unique_rc<T: MetaSized>(self) -> rc::UniqueRc<T, A>
+    where
+        I: Init<T, !, Extra>,
+    {
+        self.try_build_unique_rc().unwrap_or_else(|e| match e {})
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn try_build_cyclic_unique_rc_with<T: MetaSized, Error>(
+        self,
+    ) -> Result<rc::UniqueRc<T, A>, Error>
+    where
+        I: Init<T, Error, (rc::Weak<T, A>, Extra)>,
+        A: Clone,
+    {
+        // SAFETY: `I` implements `Init<T, _>`
+        unsafe {
+            rc::unique_rc_new_layout_impl(self.init, self.alloc, |weak| (weak.clone(), self.extra))
+        }
+        .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn build_cyclic_unique_rc_with<T: MetaSized>(self) -> rc::UniqueRc<T, A>
+    where
+        I: Init<T, !, (rc::Weak<T, A>, Extra)>,
+        A: Clone,
+    {
+        self.try_build_cyclic_unique_rc_with()
+            .unwrap_or_else(|e| match e {})
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn try_build_unique_arc<T: MetaSized, Error>(self) -> Result<arc::UniqueArc<T, A>, Error>
+    where
+        I: Init<T, Error, Extra>,
+    {
+        // SAFETY: `I` implements `Init<T, Extra>`
+        unsafe { arc::unique_arc_new_layout_impl(self.init, self.alloc, |_| self.extra) }
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn build_unique_arc<T: MetaSized>(self) -> arc::UniqueArc<T, A>
+    where
+        I: Init<T, !, Extra>,
+    {
+        self.try_build_unique_arc().unwrap_or_else(|e| match e {})
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn try_build_cyclic_unique_arc_with<T: MetaSized, Error>(
+        self,
+    ) -> Result<arc::UniqueArc<T, A>, Error>
+    where
+        I: Init<T, Error, (arc::Weak<T, A>, Extra)>,
+        A: Clone,
+    {
+        // SAFETY: `I` implements `Init<T, _>`
+        unsafe {
+            arc::unique_arc_new_layout_impl(self.init, self.alloc, |weak| {
+                (weak.clone(), self.extra)
+            })
+        }
+        .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn build_cyclic_unique_arc_with<T: MetaSized>(self) -> arc::UniqueArc<T, A>
+    where
+        I: Init<T, !, (arc::Weak<T, A>, Extra)>,
+        A: Clone,
+    {
+        self.try_build_cyclic_unique_arc_with()
+            .unwrap_or_else(|e| match e {})
+    }
 }
 
 #[allow(clippy::unit_arg, reason = "symmetry")]
 impl<I, A: Allocator> Builder<I, A, ()> {
-    pub fn try_build_cyclic_rc<T: MetaSized, Error>(self) -> Result<Rc<T, A>, Error>
+    pub fn try_build_cyclic_rc<T: MetaSized + rc::RcCyclicDst, Error>(
+        self,
+    ) -> Result<Rc<T, A>, Error>
+    where
+        I: Init<T, Error, rc::Weak<T, A>>,
+        A: Clone,
+    {
+        self.checked_build_cyclic_rc()
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_cyclic_rc`](Self::try_build_cyclic_rc), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_cyclic_rc<T: MetaSized + rc::RcCyclicDst, Error>(
+        self,
+    ) -> Result<Rc<T, A>, AllocOrInit<Error>>
     where
         I: Init<T, Error, rc::Weak<T, A>>,
         A: Clone,
     {
         // SAFETY: `I` implements `Init<T, ()>`
-        unsafe {
-            rc::rc_new_base_impl::<T, Error, A, (), rc::WeakExtra>(
-                self.init, self.alloc, self.extra,
-            )
-        }
+        unsafe { T::rc_new_cyclic_impl(self.init, self.alloc, rc::Weak::clone) }
     }
 
     /// # Safety
     ///
     /// The `rc::Weak<T, A>`s passed to `init` must be treated as pinned.
-    pub unsafe fn try_build_pinned_cyclic_rc<T: MetaSized, Error>(
+    pub unsafe fn try_build_pinned_cyclic_rc<T: MetaSized + rc::RcCyclicDst, Error>(
         self,
     ) -> Result<Pin<Rc<T, A>>, Error>
+    where
+        I: PinInit<T, Error, rc::Weak<T, A>>,
+        A: Clone + 'static,
+    {
+        // SAFETY: discharged to caller
+        unsafe { self.checked_build_pinned_cyclic_rc() }.map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_pinned_cyclic_rc`](Self::try_build_pinned_cyclic_rc), but surfaces allocation failure instead of aborting.
+    ///
+    /// # Safety
+    ///
+    /// The `rc::Weak<T, A>`s passed to `init` must be treated as pinned.
+    pub unsafe fn checked_build_pinned_cyclic_rc<T: MetaSized + rc::RcCyclicDst, Error>(
+        self,
+    ) -> Result<Pin<Rc<T, A>>, AllocOrInit<Error>>
     where
         I: PinInit<T, Error, rc::Weak<T, A>>,
         A: Clone + 'static,
     {
         // Safety: the rc is immediately pinned, the `Weak` requirement is discharged to the caller
-        let rc = unsafe {
-            rc::rc_new_base_impl::<T, Error, A, (), rc::WeakExtra>(
-                self.init, self.alloc, self.extra,
-            )
-        }?;
+        let rc = unsafe { T::rc_new_cyclic_impl(self.init, self.alloc, rc::Weak::clone) }?;
         Ok(unsafe { Pin::new_unchecked(rc) })
     }
 
-    pub fn build_cyclic_rc<T: MetaSized>(self) -> Rc<T, A>
+    pub fn build_cyclic_rc<T: MetaSized + rc::RcCyclicDst>(self) -> Rc<T, A>
     where
         I: Init<T, !, rc::Weak<T, A>>,
         A: Clone,
@@ -246,7 +578,7 @@ impl<I, A: Allocator> Builder<I, A, ()> {
     /// # Safety
     ///
     /// The `rc::Weak<T, A>`s passed to `init` must be treated as pinned.
-    pub unsafe fn build_pinned_cyclic_rc<T: MetaSized>(self) -> Pin<Rc<T, A>>
+    pub unsafe fn build_pinned_cyclic_rc<T: MetaSized + rc::RcCyclicDst>(self) -> Pin<Rc<T, A>>
     where
         I: PinInit<T, !, rc::Weak<T, A>>,
         A: Clone + 'static,
@@ -257,4 +589,127 @@ impl<I, A: Allocator> Builder<I, A, ()> {
                 .unwrap_or_else(|e| match e {})
         }
     }
+
+    pub fn try_build_cyclic_arc<T: MetaSized + arc::ArcCyclicDst, Error>(
+        self,
+    ) -> Result<Arc<T, A>, Error>
+    where
+        I: Init<T, Error, arc::Weak<T, A>>,
+        A: Clone,
+    {
+        self.checked_build_cyclic_arc()
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_cyclic_arc`](Self::try_build_cyclic_arc), but surfaces allocation failure instead of aborting.
+    pub fn checked_build_cyclic_arc<T: MetaSized + arc::ArcCyclicDst, Error>(
+        self,
+    ) -> Result<Arc<T, A>, AllocOrInit<Error>>
+    where
+        I: Init<T, Error, arc::Weak<T, A>>,
+        A: Clone,
+    {
+        // SAFETY: `I` implements `Init<T, ()>`
+        unsafe { T::arc_new_cyclic_impl(self.init, self.alloc, arc::Weak::clone) }
+    }
+
+    /// # Safety
+    ///
+    /// The `sync::Weak<T, A>`s passed to `init` must be treated as pinned.
+    pub unsafe fn try_build_pinned_cyclic_arc<T: MetaSized + arc::ArcCyclicDst, Error>(
+        self,
+    ) -> Result<Pin<Arc<T, A>>, Error>
+    where
+        I: PinInit<T, Error, arc::Weak<T, A>>,
+        A: Clone + 'static,
+    {
+        // SAFETY: discharged to caller
+        unsafe { self.checked_build_pinned_cyclic_arc() }.map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    /// Like [`try_build_pinned_cyclic_arc`](Self::try_build_pinned_cyclic_arc), but surfaces allocation failure instead of aborting.
+    ///
+    /// # Safety
+    ///
+    /// The `sync::Weak<T, A>`s passed to `init` must be treated as pinned.
+    pub unsafe fn checked_build_pinned_cyclic_arc<T: MetaSized + arc::ArcCyclicDst, Error>(
+        self,
+    ) -> Result<Pin<Arc<T, A>>, AllocOrInit<Error>>
+    where
+        I: PinInit<T, Error, arc::Weak<T, A>>,
+        A: Clone + 'static,
+    {
+        // Safety: the arc is immediately pinned, the `Weak` requirement is discharged to the caller
+        let arc = unsafe { T::arc_new_cyclic_impl(self.init, self.alloc, arc::Weak::clone) }?;
+        Ok(unsafe { Pin::new_unchecked(arc) })
+    }
+
+    pub fn build_cyclic_arc<T: MetaSized + arc::ArcCyclicDst>(self) -> Arc<T, A>
+    where
+        I: Init<T, !, arc::Weak<T, A>>,
+        A: Clone,
+    {
+        self.try_build_cyclic_arc().unwrap_or_else(|e| match e {})
+    }
+
+    /// # Safety
+    ///
+    /// The `sync::Weak<T, A>`s passed to `init` must be treated as pinned.
+    pub unsafe fn build_pinned_cyclic_arc<T: MetaSized + arc::ArcCyclicDst>(self) -> Pin<Arc<T, A>>
+    where
+        I: PinInit<T, !, arc::Weak<T, A>>,
+        A: Clone + 'static,
+    {
+        // SAFETY: discharged to caller
+        unsafe {
+            self.try_build_pinned_cyclic_arc()
+                .unwrap_or_else(|e| match e {})
+        }
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn try_build_cyclic_unique_rc<T: MetaSized, Error>(
+        self,
+    ) -> Result<rc::UniqueRc<T, A>, Error>
+    where
+        I: Init<T, Error, rc::Weak<T, A>>,
+        A: Clone,
+    {
+        // SAFETY: `I` implements `Init<T, _>`
+        unsafe { rc::unique_rc_new_layout_impl(self.init, self.alloc, rc::Weak::clone) }
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn build_cyclic_unique_rc<T: MetaSized>(self) -> rc::UniqueRc<T, A>
+    where
+        I: Init<T, !, rc::Weak<T, A>>,
+        A: Clone,
+    {
+        self.try_build_cyclic_unique_rc()
+            .unwrap_or_else(|e| match e {})
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn try_build_cyclic_unique_arc<T: MetaSized, Error>(
+        self,
+    ) -> Result<arc::UniqueArc<T, A>, Error>
+    where
+        I: Init<T, Error, arc::Weak<T, A>>,
+        A: Clone,
+    {
+        // SAFETY: `I` implements `Init<T, _>`
+        unsafe { arc::unique_arc_new_layout_impl(self.init, self.alloc, arc::Weak::clone) }
+            .map_err(AllocOrInit::handle_alloc_error)
+    }
+
+    #[cfg(feature = "assume-rc-layout")]
+    pub fn build_cyclic_unique_arc<T: MetaSized>(self) -> arc::UniqueArc<T, A>
+    where
+        I: Init<T, !, arc::Weak<T, A>>,
+        A: Clone,
+    {
+        self.try_build_cyclic_unique_arc()
+            .unwrap_or_else(|e| match e {})
+    }
 }
@@ -1,70 +1,220 @@
-use core::{marker::MetaSized, pin::Pin};
+use core::{alloc::Layout, marker::MetaSized, mem::MaybeUninit, pin::Pin};
 
 use alloc::alloc::{Allocator, Global};
-pub(crate) use alloc::rc::{Rc, Weak};
+pub(crate) use alloc::rc::{Rc, UniqueRc, Weak};
 
-use crate::{Init, PinInit};
+use crate::{Init, InitFromIterError, PinInit};
 
-pub(crate) trait MaybeWeakExtra<T: MetaSized, A: Allocator, InputExtra = ()>: Sized {
-    type OutputExtra: Sized;
-    fn make(weak: &Weak<T, A>, input: InputExtra) -> Self::OutputExtra;
-}
+use super::{error::AllocOrInit, layout::layout_for_metadata};
 
-pub(crate) struct WeakExtra;
+/// Types that an `Rc` can be emplaced into.
+///
+/// Without the `assume-rc-layout` feature, this is implemented for sized types, slices, and `str`,
+/// which are allocated through `Rc`'s public API (e.g. [`Rc::new_uninit_in`]).
+///
+/// With the `assume-rc-layout` feature, this is implemented for all types, by constructing
+/// `Rc`'s heap allocation manually. Note that this relies on the layout of `Rc`'s heap allocation,
+/// which is not stable.
+///
+/// # Safety
+///
+/// This trait should not be implemented outside of this crate.
+pub unsafe trait RcDst: MetaSized {
+    /// # Safety
+    ///
+    /// Either `init` implements `Init`, or the returned `Rc` is treated as pinned.
+    #[doc(hidden)]
+    unsafe fn rc_new_impl<Error, A: Allocator, Extra>(
+        init: impl PinInit<Self, Error, Extra>,
+        alloc: A,
+        extra: Extra,
+    ) -> Result<Rc<Self, A>, AllocOrInit<Error>>;
+}
 
-impl<T: MetaSized, A: Allocator + Clone> MaybeWeakExtra<T, A> for WeakExtra {
-    type OutputExtra = Weak<T, A>;
+/// Types that an `Rc` can be emplaced into while handing out `Weak`s to the allocation.
+///
+/// Without the `assume-rc-layout` feature, this is implemented for sized types,
+/// which are allocated through [`UniqueRc`](alloc::rc::UniqueRc).
+///
+/// With the `assume-rc-layout` feature, this is implemented for all types. See [`RcDst`].
+///
+/// # Safety
+///
+/// This trait should not be implemented outside of this crate.
+pub unsafe trait RcCyclicDst: RcDst {
+    /// # Safety
+    ///
+    /// Either `init` implements `Init`, or the returned `Rc` and the `Weak`s passed to `make_extra`
+    /// are treated as pinned.
+    #[doc(hidden)]
+    unsafe fn rc_new_cyclic_impl<Error, A: Allocator + Clone, Extra>(
+        init: impl PinInit<Self, Error, Extra>,
+        alloc: A,
+        make_extra: impl FnOnce(&Weak<Self, A>) -> Extra,
+    ) -> Result<Rc<Self, A>, AllocOrInit<Error>>;
+}
 
-    fn make(weak: &Weak<T, A>, _: ()) -> Weak<T, A> {
-        weak.clone()
+#[cfg(not(feature = "assume-rc-layout"))]
+unsafe impl<T> RcDst for T {
+    unsafe fn rc_new_impl<Error, A: Allocator, Extra>(
+        init: impl PinInit<T, Error, Extra>,
+        alloc: A,
+        extra: Extra,
+    ) -> Result<Rc<T, A>, AllocOrInit<Error>> {
+        let mut rc = Rc::<T, A>::try_new_uninit_in(alloc)
+            .map_err(|_| AllocOrInit::Alloc(Layout::new::<T>()))?;
+        let dst = Rc::get_mut(&mut rc).unwrap().as_mut_ptr();
+        // dropping `rc` on error or panic deallocates without dropping the value
+        unsafe { init.init(dst, extra) }.map_err(AllocOrInit::Init)?;
+        // SAFETY: we just initialized the value
+        Ok(unsafe { rc.assume_init() })
     }
 }
 
-pub(crate) struct NonWeakExtra;
+#[cfg(not(feature = "assume-rc-layout"))]
+unsafe impl<T> RcDst for [T] {
+    unsafe fn rc_new_impl<Error, A: Allocator, Extra>(
+        init: impl PinInit<[T], Error, Extra>,
+        alloc: A,
+        extra: Extra,
+    ) -> Result<Rc<[T], A>, AllocOrInit<Error>> {
+        let len = init.metadata();
+        // There is no fallible `new_uninit_slice_in`, so only layout overflow is surfaced;
+        // allocation failure still aborts.
+        check_rc_layout(layout_for_metadata::<[T]>(len)?)?;
+        let mut rc = Rc::<[T], A>::new_uninit_slice_in(len, alloc);
+        let dst = Rc::get_mut(&mut rc).unwrap() as *mut [_] as *mut [T];
+        // dropping `rc` on error or panic deallocates without dropping the elements
+        unsafe { init.init(dst, extra) }.map_err(AllocOrInit::Init)?;
+        // SAFETY: we just initialized the elements
+        Ok(unsafe { rc.assume_init() })
+    }
+}
 
-impl<T: MetaSized, A: Allocator, Extra> MaybeWeakExtra<T, A, Extra> for NonWeakExtra {
-    type OutputExtra = Extra;
+#[cfg(not(feature = "assume-rc-layout"))]
+unsafe impl RcDst for str {
+    unsafe fn rc_new_impl<Error, A: Allocator, Extra>(
+        init: impl PinInit<str, Error, Extra>,
+        alloc: A,
+        extra: Extra,
+    ) -> Result<Rc<str, A>, AllocOrInit<Error>> {
+        let len = init.metadata();
+        // See the `[T]` impl above.
+        check_rc_layout(layout_for_metadata::<str>(len)?)?;
+        let mut rc = Rc::<[u8], A>::new_uninit_slice_in(len, alloc);
+        let dst = Rc::get_mut(&mut rc).unwrap() as *mut [_] as *mut str;
+        // dropping `rc` on error or panic deallocates
+        unsafe { init.init(dst, extra) }.map_err(AllocOrInit::Init)?;
+        // SAFETY: we just initialized the bytes
+        let (ptr, alloc) = Rc::into_raw_with_allocator(unsafe { rc.assume_init() });
+        // SAFETY: `str` has the same layout as `[u8]`, and `init` wrote valid UTF-8
+        Ok(unsafe { Rc::from_raw_in(ptr as *const str, alloc) })
+    }
+}
 
-    fn make(_weak: &Weak<T, A>, input: Extra) -> Extra {
-        input
+#[cfg(not(feature = "assume-rc-layout"))]
+unsafe impl<T> RcCyclicDst for T {
+    unsafe fn rc_new_cyclic_impl<Error, A: Allocator + Clone, Extra>(
+        init: impl PinInit<T, Error, Extra>,
+        alloc: A,
+        make_extra: impl FnOnce(&Weak<T, A>) -> Extra,
+    ) -> Result<Rc<T, A>, AllocOrInit<Error>> {
+        // There is no fallible `UniqueRc::new_in`, so allocation failure aborts.
+        // SAFETY: discharged to caller
+        let uninit = unsafe {
+            unique_rc_new_uninit_impl(init, alloc, |uninit| make_extra(&downgrade_uninit(uninit)))
+        }
+        .map_err(AllocOrInit::Init)?;
+        // SAFETY: the value is initialized
+        Ok(unsafe { UniqueRc::into_rc(uninit).assume_init() })
     }
 }
 
-pub(crate) struct WithWeakExtra;
+/// Checks that the size of `Rc`'s heap allocation (two counts followed by the value) does not overflow.
+#[cfg(not(feature = "assume-rc-layout"))]
+fn check_rc_layout(value_layout: Layout) -> Result<(), core::alloc::LayoutError> {
+    Layout::new::<[usize; 2]>().extend(value_layout)?;
+    Ok(())
+}
+
+/// Emplace a value into a new `UniqueRc`, which is returned still wrapped in `MaybeUninit`.
+///
+/// `Weak`s to a `UniqueRc` cannot be upgraded until it is converted into an `Rc`,
+/// so they can be handed out to `init` through `make_extra`.
+///
+/// # Safety
+///
+/// Either `init` implements `Init`, or the value and the `Weak`s created in `make_extra`
+/// are treated as pinned.
+///
+/// If this returns `Ok(_)`, the value is initialized.
+#[cfg(not(feature = "assume-rc-layout"))]
+unsafe fn unique_rc_new_uninit_impl<T, Error, A: Allocator, Extra>(
+    init: impl PinInit<T, Error, Extra>,
+    alloc: A,
+    make_extra: impl FnOnce(&UniqueRc<MaybeUninit<T>, A>) -> Extra,
+) -> Result<UniqueRc<MaybeUninit<T>, A>, Error> {
+    // `UniqueRc` is the only way to hand out `Weak`s before the value is initialized, but it
+    // has no `new_uninit_in`, so the uninitialized `MaybeUninit<T>` is passed by value and may
+    // be materialized on the stack for large `T`. Non-cyclic `Rc`s use `Rc::try_new_uninit_in`.
+    let mut uninit = UniqueRc::new_in(MaybeUninit::<T>::uninit(), alloc);
+    let extra = make_extra(&uninit);
+    // dropping `uninit` on error or panic deallocates (or leaves the allocation to the
+    // remaining `Weak`s) without dropping the value
+    unsafe { init.init(uninit.as_mut_ptr(), extra) }?;
+    Ok(uninit)
+}
+
+#[cfg(not(feature = "assume-rc-layout"))]
+fn downgrade_uninit<T, A: Allocator + Clone>(uninit: &UniqueRc<MaybeUninit<T>, A>) -> Weak<T, A> {
+    let (weak, alloc) = Weak::into_raw_with_allocator(UniqueRc::downgrade(uninit));
+    // SAFETY: `MaybeUninit<T>` has the same size and alignment as `T`
+    unsafe { Weak::from_raw_in(weak.cast::<T>(), alloc) }
+}
 
-impl<T: MetaSized, A: Allocator + Clone, Extra> MaybeWeakExtra<T, A, Extra> for WithWeakExtra {
-    type OutputExtra = (Weak<T, A>, Extra);
-    fn make(weak: &Weak<T, A>, extra: Extra) -> (Weak<T, A>, Extra) {
-        (weak.clone(), extra)
+#[cfg(feature = "assume-rc-layout")]
+unsafe impl<T: MetaSized> RcDst for T {
+    unsafe fn rc_new_impl<Error, A: Allocator, Extra>(
+        init: impl PinInit<T, Error, Extra>,
+        alloc: A,
+        extra: Extra,
+    ) -> Result<Rc<T, A>, AllocOrInit<Error>> {
+        // SAFETY: discharged to caller
+        unsafe { unique_rc_new_layout_impl(init, alloc, |_| extra) }.map(UniqueRc::into_rc)
     }
 }
 
+#[cfg(feature = "assume-rc-layout")]
+unsafe impl<T: MetaSized> RcCyclicDst for T {
+    unsafe fn rc_new_cyclic_impl<Error, A: Allocator + Clone, Extra>(
+        init: impl PinInit<T, Error, Extra>,
+        alloc: A,
+        make_extra: impl FnOnce(&Weak<T, A>) -> Extra,
+    ) -> Result<Rc<T, A>, AllocOrInit<Error>> {
+        // SAFETY: discharged to caller
+        unsafe { unique_rc_new_layout_impl(init, alloc, make_extra) }.map(UniqueRc::into_rc)
+    }
+}
+
+/// Emplace a value into a new `UniqueRc`, while handing out `Weak`s to it through `make_extra`.
+///
 /// # Safety
 ///
-/// Either `init` implements `Init`, or the returned `Rc` and `rc::Weak`s passed as extras (if any)
-/// are treated as pinned.
+/// Either `init` implements `Init`, or the returned `UniqueRc` and `rc::Weak`s passed to
+/// `make_extra` are treated as pinned.
 ///
-/// Also, this assumes the layout of `Rc`'s heap allocation, which is not stable.
-pub(crate) unsafe fn rc_new_base_impl<
-    T: MetaSized,
-    Error,
-    A: Allocator,
-    InputExtra,
-    WeakExtra: MaybeWeakExtra<T, A, InputExtra>,
->(
-    init: impl PinInit<T, Error, WeakExtra::OutputExtra>,
+/// Also, this assumes the layout of `Rc`'s heap allocation, and that `UniqueRc<T, A>` has the same
+/// layout as `Weak<T, A>`, neither of which is stable.
+#[cfg(feature = "assume-rc-layout")]
+pub(crate) unsafe fn unique_rc_new_layout_impl<T: MetaSized, Error, A: Allocator, Extra>(
+    init: impl PinInit<T, Error, Extra>,
     alloc: A,
-    extra: InputExtra,
-) -> Result<Rc<T, A>, Error> {
+    make_extra: impl FnOnce(&Weak<T, A>) -> Extra,
+) -> Result<UniqueRc<T, A>, AllocOrInit<Error>> {
     // NOTE: this is unsound; it relies on the unstable layout of Rc's heap allocation
-    use core::alloc::Layout;
     use core::cell::Cell;
     let metadata = init.metadata();
-    // SAFETY: this is unsound, size could overflow
-    // FIXME: should use checked_layout_for_meta if/when that's a thing
-    let value_layout = unsafe {
-        Layout::for_value_raw::<T>(core::ptr::from_raw_parts(core::ptr::null::<()>(), metadata))
-    };
+    let value_layout = layout_for_metadata::<T>(metadata)?;
 
     #[repr(C)]
     struct RcCounts {
@@ -72,14 +222,14 @@ pub(crate) unsafe fn rc_new_base_impl<
         weak: Cell<usize>,
     }
 
-    let (layout, offset) = Layout::new::<RcCounts>().extend(value_layout).unwrap();
+    let (layout, offset) = Layout::new::<RcCounts>().extend(value_layout)?;
 
     let base_ptr = if layout.size() == 0 {
         layout.dangling()
     } else {
         match alloc.allocate(layout) {
             Ok(ptr) => ptr.cast(),
-            Err(_) => alloc::alloc::handle_alloc_error(layout),
+            Err(_) => return Err(AllocOrInit::Alloc(layout)),
         }
     };
 
@@ -95,49 +245,70 @@ pub(crate) unsafe fn rc_new_base_impl<
 
     let weak = unsafe { Weak::from_raw_in(value_ptr, alloc) };
 
-    let extra = WeakExtra::make(&weak, extra);
+    let extra = make_extra(&weak);
 
     match unsafe { init.init(value_ptr, extra) } {
+        // Like `weak`, a `UniqueRc` holds the implicit weak reference while the strong count is 0,
+        // so it takes over `weak`.
+        // NOTE: there is no `UniqueRc::from_raw_in`, so this relies on the layout of `UniqueRc`
         Ok(()) => Ok(unsafe {
-            let (_, alloc) = Weak::into_raw_with_allocator(weak);
-            base_ptr.cast::<RcCounts>().as_ref().strong.set(1);
-
-            Rc::from_raw_in(value_ptr, alloc)
+            const { assert!(size_of::<Weak<T, A>>() == size_of::<UniqueRc<T, A>>()) };
+            core::ptr::from_ref(&*core::mem::ManuallyDrop::new(weak))
+                .cast::<UniqueRc<T, A>>()
+                .read()
         }),
         // dropping `weak` in this branch deallocates
-        Err(err) => Err(err),
+        Err(err) => Err(AllocOrInit::Init(err)),
     }
 }
 
-pub fn try_rc_new<T: MetaSized, Error>(init: impl Init<T, Error>) -> Result<Rc<T>, Error> {
+pub fn try_rc_new<T: MetaSized + RcDst, Error>(init: impl Init<T, Error>) -> Result<Rc<T>, Error> {
     // Safety: `init` implements `Init<T>`
-    unsafe { rc_new_base_impl::<T, Error, Global, (), NonWeakExtra>(init, Global, ()) }
+    unsafe { T::rc_new_impl(init, Global, ()) }.map_err(AllocOrInit::handle_alloc_error)
 }
-pub fn rc_new<T: MetaSized>(init: impl Init<T>) -> Rc<T> {
+pub fn rc_new<T: MetaSized + RcDst>(init: impl Init<T>) -> Rc<T> {
     try_rc_new(init).unwrap_or_else(|e| match e {})
 }
-pub fn try_rc_new_pinned<T: MetaSized, Error>(
+/// Collect an iterator into a new `Rc<[T]>`, without an intermediate `Vec`.
+pub fn try_rc_new_from_iter<T>(
+    iter: impl IntoIterator<IntoIter: ExactSizeIterator<Item = T>>,
+) -> Result<Rc<[T]>, InitFromIterError> {
+    try_rc_new(crate::from_iter(iter))
+}
+/// Collect an iterator into a new `Rc<[T]>`, without an intermediate `Vec`.
+///
+/// # Panics
+///
+/// Panics if the iterator does not yield exactly as many items as its `len()`.
+pub fn rc_new_from_iter<T>(
+    iter: impl IntoIterator<IntoIter: ExactSizeIterator<Item = T>>,
+) -> Rc<[T]> {
+    try_rc_new_from_iter(iter).unwrap_or_else(|e| panic!("{e}"))
+}
+pub fn try_rc_new_pinned<T: MetaSized + RcDst, Error>(
     init: impl PinInit<T, Error>,
 ) -> Result<Pin<Rc<T>>, Error> {
     // Safety: the `Rc` is immediately pinned
-    let rc = unsafe { rc_new_base_impl::<T, Error, Global, (), NonWeakExtra>(init, Global, ()) }?;
+    let rc =
+        unsafe { T::rc_new_impl(init, Global, ()) }.map_err(AllocOrInit::handle_alloc_error)?;
     // SAFETY: No other code has had access to this `Rc`.
     Ok(unsafe { Pin::new_unchecked(rc) })
 }
-pub fn rc_new_pinned<T: MetaSized>(init: impl PinInit<T>) -> Pin<Rc<T>> {
+pub fn rc_new_pinned<T: MetaSized + RcDst>(init: impl PinInit<T>) -> Pin<Rc<T>> {
     try_rc_new_pinned(init).unwrap_or_else(|e| match e {})
 }
 
 /// Create a new `Rc<T>` while giving you a `Weak<T>` to the allocation.
-pub fn try_rc_new_cyclic<T: MetaSized, Error>(
+pub fn try_rc_new_cyclic<T: MetaSized + RcCyclicDst, Error>(
     init: impl Init<T, Error, Weak<T>>,
 ) -> Result<Rc<T>, Error> {
     // Safety: `init` implements `Init<T>`
-    unsafe { rc_new_base_impl::<T, Error, Global, (), WeakExtra>(init, Global, ()) }
+    unsafe { T::rc_new_cyclic_impl(init, Global, Weak::clone) }
+        .map_err(AllocOrInit::handle_alloc_error)
 }
 
 /// Create a new `Rc<T>` while giving you a `Weak<T>` to the allocation.
-pub fn rc_new_cyclic<T: MetaSized>(init: impl Init<T, !, Weak<T>>) -> Rc<T> {
+pub fn rc_new_cyclic<T: MetaSized + RcCyclicDst>(init: impl Init<T, !, Weak<T>>) -> Rc<T> {
     try_rc_new_cyclic(init).unwrap_or_else(|e| match e {})
 }
 
@@ -146,11 +317,12 @@ pub fn rc_new_cyclic<T: MetaSized>(init: impl Init<T, !, Weak<T>>) -> Rc<T> {
 /// # Safety
 ///
 /// `init` must treat the `Weak`s passed to it as pinned.
-pub unsafe fn try_rc_new_cyclic_pinned<T: MetaSized, Error>(
+pub unsafe fn try_rc_new_cyclic_pinned<T: MetaSized + RcCyclicDst, Error>(
     init: impl PinInit<T, Error, Weak<T>>,
 ) -> Result<Pin<Rc<T>>, Error> {
     // Safety: the `Rc` is immediately pinned
-    let rc = unsafe { rc_new_base_impl::<T, Error, Global, (), WeakExtra>(init, Global, ()) }?;
+    let rc = unsafe { T::rc_new_cyclic_impl(init, Global, Weak::clone) }
+        .map_err(AllocOrInit::handle_alloc_error)?;
     // SAFETY: The only code that has had access to this Rc has had access as `Weak<T>`,
     // which the caller must ensure are treated as pinned.
     Ok(unsafe { Pin::new_unchecked(rc) })
@@ -161,7 +333,150 @@ pub unsafe fn try_rc_new_cyclic_pinned<T: MetaSized, Error>(
 /// # Safety
 ///
 /// `init` must treat the `Weak`s passed to it as pinned.
-pub unsafe fn rc_new_cyclic_pinned<T: MetaSized>(init: impl PinInit<T, !, Weak<T>>) -> Pin<Rc<T>> {
+pub unsafe fn rc_new_cyclic_pinned<T: MetaSized + RcCyclicDst>(
+    init: impl PinInit<T, !, Weak<T>>,
+) -> Pin<Rc<T>> {
     // SAFETY: discharged to caller
     unsafe { try_rc_new_cyclic_pinned(init).unwrap_or_else(|e| match e {}) }
 }
+
+/// Like [`try_rc_new`], but surfaces allocation failure instead of aborting.
+///
+/// Without the `assume-rc-layout` feature, allocation failure for slices and `str` still aborts,
+/// since `Rc` has no fallible API to allocate them.
+pub fn checked_rc_new<T: MetaSized + RcDst, Error>(
+    init: impl Init<T, Error>,
+) -> Result<Rc<T>, AllocOrInit<Error>> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { T::rc_new_impl(init, Global, ()) }
+}
+/// Like [`try_rc_new_pinned`], but surfaces allocation failure instead of aborting.
+///
+/// See [`checked_rc_new`].
+pub fn checked_rc_new_pinned<T: MetaSized + RcDst, Error>(
+    init: impl PinInit<T, Error>,
+) -> Result<Pin<Rc<T>>, AllocOrInit<Error>> {
+    // Safety: the `Rc` is immediately pinned
+    let rc = unsafe { T::rc_new_impl(init, Global, ()) }?;
+    // SAFETY: No other code has had access to this `Rc`.
+    Ok(unsafe { Pin::new_unchecked(rc) })
+}
+
+/// Like [`try_rc_new_cyclic`], but surfaces allocation failure instead of aborting.
+///
+/// Without the `assume-rc-layout` feature, allocation failure still aborts,
+/// since `UniqueRc` has no fallible API.
+pub fn checked_rc_new_cyclic<T: MetaSized + RcCyclicDst, Error>(
+    init: impl Init<T, Error, Weak<T>>,
+) -> Result<Rc<T>, AllocOrInit<Error>> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { T::rc_new_cyclic_impl(init, Global, Weak::clone) }
+}
+
+/// Like [`try_rc_new_cyclic_pinned`], but surfaces allocation failure instead of aborting.
+///
+/// See [`checked_rc_new_cyclic`].
+///
+/// # Safety
+///
+/// `init` must treat the `Weak`s passed to it as pinned.
+pub unsafe fn checked_rc_new_cyclic_pinned<T: MetaSized + RcCyclicDst, Error>(
+    init: impl PinInit<T, Error, Weak<T>>,
+) -> Result<Pin<Rc<T>>, AllocOrInit<Error>> {
+    // Safety: the `Rc` is immediately pinned
+    let rc = unsafe { T::rc_new_cyclic_impl(init, Global, Weak::clone) }?;
+    // SAFETY: The only code that has had access to this Rc has had access as `Weak<T>`,
+    // which the caller must ensure are treated as pinned.
+    Ok(unsafe { Pin::new_unchecked(rc) })
+}
+
+/// Create a new `UniqueRc<T>`.
+#[cfg(feature = "assume-rc-layout")]
+pub fn try_unique_rc_new<T: MetaSized, Error>(
+    init: impl Init<T, Error>,
+) -> Result<UniqueRc<T>, Error> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { unique_rc_new_layout_impl(init, Global, |_| ()) }
+        .map_err(AllocOrInit::handle_alloc_error)
+}
+
+/// Create a new `UniqueRc<T>`.
+#[cfg(feature = "assume-rc-layout")]
+pub fn unique_rc_new<T: MetaSized>(init: impl Init<T>) -> UniqueRc<T> {
+    try_unique_rc_new(init).unwrap_or_else(|e| match e {})
+}
+
+/// Create a new `UniqueRc<T>` while giving you a `Weak<T>` to the allocation.
+///
+/// The `Weak`s cannot be upgraded until the `UniqueRc` is converted into an `Rc`.
+#[cfg(feature = "assume-rc-layout")]
+pub fn try_unique_rc_new_cyclic<T: MetaSized, Error>(
+    init: impl Init<T, Error, Weak<T>>,
+) -> Result<UniqueRc<T>, Error> {
+    // Safety: `init` implements `Init<T>`
+    unsafe { unique_rc_new_layout_impl(init, Global, Weak::clone) }
+        .map_err(AllocOrInit::handle_alloc_error)
+}
+
+/// Create a new `UniqueRc<T>` while giving you a `Weak<T>` to the allocation.
+///
+/// The `Weak`s cannot be upgraded until the `UniqueRc` is converted into an `Rc`.
+#[cfg(feature = "assume-rc-layout")]
+pub fn unique_rc_new_cyclic<T: MetaSized>(init: impl Init<T, !, Weak<T>>) -> UniqueRc<T> {
+    try_unique_rc_new_cyclic(init).unwrap_or_else(|e| match e {})
+}
+
+/// Initialize the value in an `Rc<MaybeUninit<T>>`.
+///
+/// If `init` fails or panics, the `Rc` is dropped.
+///
+/// # Panics
+///
+/// Panics if there are other `Rc` or `Weak` pointers to the same allocation.
+pub fn try_init_rc<T, Error, A: Allocator>(
+    mut rc: Rc<MaybeUninit<T>, A>,
+    init: impl Init<T, Error>,
+) -> Result<Rc<T, A>, Error> {
+    let slot = Rc::get_mut(&mut rc).expect("the `Rc` is not unique");
+    crate::try_initialize(slot, init)?;
+    // SAFETY: we just initialized the value
+    Ok(unsafe { rc.assume_init() })
+}
+/// Initialize the value in an `Rc<MaybeUninit<T>>`.
+///
+/// # Panics
+///
+/// Panics if there are other `Rc` or `Weak` pointers to the same allocation.
+pub fn init_rc<T, A: Allocator>(rc: Rc<MaybeUninit<T>, A>, init: impl Init<T>) -> Rc<T, A> {
+    try_init_rc(rc, init).unwrap_or_else(|e| match e {})
+}
+
+/// Initialize the elements in an `Rc<[MaybeUninit<T>]>`.
+///
+/// If `init` fails or panics, the `Rc` is dropped.
+///
+/// # Panics
+///
+/// Panics if there are other `Rc` or `Weak` pointers to the same allocation, or if `init`'s
+/// length does not match the length of the slice.
+pub fn try_init_rc_slice<T, Error, A: Allocator>(
+    mut rc: Rc<[MaybeUninit<T>], A>,
+    init: impl Init<[T], Error>,
+) -> Result<Rc<[T], A>, Error> {
+    let slot = Rc::get_mut(&mut rc).expect("the `Rc` is not unique");
+    crate::try_initialize_slice(slot, init)?;
+    // SAFETY: we just initialized the elements
+    Ok(unsafe { rc.assume_init() })
+}
+/// Initialize the elements in an `Rc<[MaybeUninit<T>]>`.
+///
+/// # Panics
+///
+/// Panics if there are other `Rc` or `Weak` pointers to the same allocation, or if `init`'s
+/// length does not match the length of the slice.
+pub fn init_rc_slice<T, A: Allocator>(
+    rc: Rc<[MaybeUninit<T>], A>,
+    init: impl Init<[T]>,
+) -> Rc<[T], A> {
+    try_init_rc_slice(rc, init).unwrap_or_else(|e| match e {})
+}
@@ -1,38 +1,187 @@
-use core::mem::MaybeUninit;
+use core::ops::RangeBounds;
 
 use alloc::string::String;
 
 use crate::Init;
 
+use super::error::AllocOrInit;
+
 pub fn try_new_string<Error>(init: impl Init<str, Error>) -> Result<String, Error> {
     crate::try_new_boxed(init).map(String::from)
 }
 pub fn new_string(init: impl Init<str, !>) -> String {
     String::from(crate::new_boxed(init))
 }
+/// Like [`try_new_string`], but surfaces allocation failure instead of aborting.
+pub fn checked_new_string<Error>(
+    init: impl Init<str, Error>,
+) -> Result<String, AllocOrInit<Error>> {
+    crate::checked_new_boxed(init).map(String::from)
+}
 
 pub trait StringExt {
     fn try_append_emplace<Error>(&mut self, init: impl Init<str, Error>) -> Result<(), Error>;
     fn append_emplace(&mut self, init: impl Init<str>);
+
+    /// Emplace a string slice at byte position `index`.
+    ///
+    /// If `init` fails or panics, the bytes after `index` are shifted back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is larger than the `String`'s length, or if it does not lie on a [`char`] boundary.
+    fn try_insert_str_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<str, Error>,
+    ) -> Result<(), Error>;
+    /// Emplace a string slice at byte position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is larger than the `String`'s length, or if it does not lie on a [`char`] boundary.
+    fn insert_str_emplace(&mut self, index: usize, init: impl Init<str>);
+
+    /// Replace the specified byte range with an emplaced string slice.
+    ///
+    /// If `init` fails or panics, the bytes after `range` are shifted back, so `range` is removed from the string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point or end point do not lie on a [`char`] boundary, or if they're out of bounds.
+    fn try_replace_range_emplace<Error>(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        init: impl Init<str, Error>,
+    ) -> Result<(), Error>;
+    /// Replace the specified byte range with an emplaced string slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point or end point do not lie on a [`char`] boundary, or if they're out of bounds.
+    fn replace_range_emplace(&mut self, range: impl RangeBounds<usize>, init: impl Init<str>);
+
+    /// Like [`try_append_emplace`](StringExt::try_append_emplace), but surfaces allocation failure instead of aborting.
+    fn checked_append_emplace<Error>(
+        &mut self,
+        init: impl Init<str, Error>,
+    ) -> Result<(), AllocOrInit<Error>>;
+    /// Like [`try_insert_str_emplace`](StringExt::try_insert_str_emplace), but surfaces allocation failure instead of aborting.
+    fn checked_insert_str_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<str, Error>,
+    ) -> Result<(), AllocOrInit<Error>>;
+    /// Like [`try_replace_range_emplace`](StringExt::try_replace_range_emplace), but surfaces allocation failure instead of aborting.
+    fn checked_replace_range_emplace<Error>(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        init: impl Init<str, Error>,
+    ) -> Result<(), AllocOrInit<Error>>;
 }
 
 impl StringExt for String {
     fn try_append_emplace<Error>(&mut self, init: impl Init<str, Error>) -> Result<(), Error> {
         let additional = init.metadata();
         self.reserve(additional);
-        let len = self.len();
+        // SAFETY: we reserved `additional` bytes, `dst` has the metadata `init` asked for, and
+        // `init` writes a valid `str`
         unsafe {
-            let this = self.as_mut_vec();
-            init.init(
-                &mut this.spare_capacity_mut()[..additional] as *mut [MaybeUninit<u8>] as *mut str,
-                (),
-            )?;
-            this.set_len(len + additional);
-        };
-        Ok(())
+            super::vec::append_emplace_impl(self.as_mut_vec(), additional, |dst| {
+                init.init(dst as *mut str, ())
+            })
+        }
     }
 
     fn append_emplace(&mut self, init: impl Init<str>) {
         self.try_append_emplace(init).unwrap_or_else(|e| match e {});
     }
+
+    fn try_insert_str_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<str, Error>,
+    ) -> Result<(), Error> {
+        assert!(self.is_char_boundary(index));
+        self.try_replace_range_emplace(index..index, init)
+    }
+
+    fn insert_str_emplace(&mut self, index: usize, init: impl Init<str>) {
+        self.try_insert_str_emplace(index, init)
+            .unwrap_or_else(|e| match e {});
+    }
+
+    fn try_replace_range_emplace<Error>(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        init: impl Init<str, Error>,
+    ) -> Result<(), Error> {
+        let core::ops::Range { start, end } = core::slice::range(range, ..self.len());
+        assert!(self.is_char_boundary(start));
+        assert!(self.is_char_boundary(end));
+        let additional = init.metadata();
+        // SAFETY: the replaced range lies on char boundaries, and `init` writes a valid `str`
+        unsafe {
+            super::vec::splice_emplace_impl(self.as_mut_vec(), start..end, additional, |dst| {
+                init.init(dst as *mut str, ())
+            })
+        }
+    }
+
+    fn replace_range_emplace(&mut self, range: impl RangeBounds<usize>, init: impl Init<str>) {
+        self.try_replace_range_emplace(range, init)
+            .unwrap_or_else(|e| match e {});
+    }
+
+    fn checked_append_emplace<Error>(
+        &mut self,
+        init: impl Init<str, Error>,
+    ) -> Result<(), AllocOrInit<Error>> {
+        let additional = init.metadata();
+        self.try_reserve(additional)?;
+        // SAFETY: we reserved `additional` bytes, `dst` has the metadata `init` asked for, and
+        // `init` writes a valid `str`
+        unsafe {
+            super::vec::append_emplace_impl(self.as_mut_vec(), additional, |dst| {
+                init.init(dst as *mut str, ())
+            })
+        }
+        .map_err(AllocOrInit::Init)
+    }
+
+    fn checked_insert_str_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<str, Error>,
+    ) -> Result<(), AllocOrInit<Error>> {
+        assert!(self.is_char_boundary(index));
+        let additional = init.metadata();
+        self.try_reserve(additional)?;
+        // SAFETY: `index` lies on a char boundary, and `init` writes a valid `str`
+        unsafe {
+            super::vec::splice_emplace_impl(self.as_mut_vec(), index..index, additional, |dst| {
+                init.init(dst as *mut str, ())
+            })
+        }
+        .map_err(AllocOrInit::Init)
+    }
+
+    fn checked_replace_range_emplace<Error>(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        init: impl Init<str, Error>,
+    ) -> Result<(), AllocOrInit<Error>> {
+        let core::ops::Range { start, end } = core::slice::range(range, ..self.len());
+        assert!(self.is_char_boundary(start));
+        assert!(self.is_char_boundary(end));
+        let additional = init.metadata();
+        self.try_reserve(additional.saturating_sub(end - start))?;
+        // SAFETY: the replaced range lies on char boundaries, and `init` writes a valid `str`
+        unsafe {
+            super::vec::splice_emplace_impl(self.as_mut_vec(), start..end, additional, |dst| {
+                init.init(dst as *mut str, ())
+            })
+        }
+        .map_err(AllocOrInit::Init)
+    }
 }
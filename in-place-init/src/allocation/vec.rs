@@ -1,15 +1,23 @@
-use core::mem::MaybeUninit;
+use core::{mem::MaybeUninit, ops::RangeBounds};
 
 use alloc::{alloc::Allocator, vec::Vec};
 
 use crate::Init;
 
+use super::error::AllocOrInit;
+
 pub fn try_new_vec<T, Error>(init: impl Init<[T], Error>) -> Result<Vec<T>, Error> {
     crate::try_new_boxed(init).map(Vec::from)
 }
 pub fn new_vec<T>(init: impl Init<[T]>) -> Vec<T> {
     Vec::from(crate::new_boxed(init))
 }
+/// Like [`try_new_vec`], but surfaces allocation failure instead of aborting.
+pub fn checked_new_vec<T, Error>(
+    init: impl Init<[T], Error>,
+) -> Result<Vec<T>, AllocOrInit<Error>> {
+    crate::checked_new_boxed(init).map(Vec::from)
+}
 
 /// # Safety
 ///
@@ -50,6 +58,206 @@ pub unsafe trait VecExt {
     ) -> Result<(), Error>;
 
     fn extend_emplace(&mut self, iter: impl IntoIterator<Item: Init<Self::Item>>);
+
+    /// Emplace an element at position `index`, shifting all elements after it to the right.
+    ///
+    /// If `init` fails or panics, the elements after `index` are shifted back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    fn try_insert_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<Self::Item, Error>,
+    ) -> Result<(), Error>;
+    /// Emplace an element at position `index`, shifting all elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    fn insert_emplace(&mut self, index: usize, init: impl Init<Self::Item>);
+
+    /// Emplace a slice of elements at position `index`, shifting all elements after it to the right.
+    ///
+    /// If `init` fails or panics, the elements after `index` are shifted back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    fn try_insert_slice_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<[Self::Item], Error>,
+    ) -> Result<(), Error>;
+    /// Emplace a slice of elements at position `index`, shifting all elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    fn insert_slice_emplace(&mut self, index: usize, init: impl Init<[Self::Item]>);
+
+    /// Replace the elements in `range` with an emplaced slice of elements.
+    ///
+    /// The elements in `range` are dropped before `init` is run.
+    /// If `init` fails or panics, the elements after `range` are shifted back,
+    /// so `range` is removed from the vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the end point is greater than the length of the vector.
+    fn try_splice_emplace<Error>(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        init: impl Init<[Self::Item], Error>,
+    ) -> Result<(), Error>;
+    /// Replace the elements in `range` with an emplaced slice of elements.
+    ///
+    /// The elements in `range` are dropped before `init` is run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting point is greater than the end point or if the end point is greater than the length of the vector.
+    fn splice_emplace(&mut self, range: impl RangeBounds<usize>, init: impl Init<[Self::Item]>);
+
+    /// Resize the vector in-place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the vector is extended by emplacing initializers returned by `factory`.
+    /// If `new_len` is less than `len`, the vector is truncated.
+    ///
+    /// If an initializer fails or panics, the elements that were already emplaced are kept.
+    fn try_resize_emplace<Error, I: Init<Self::Item, Error>>(
+        &mut self,
+        new_len: usize,
+        factory: impl FnMut() -> I,
+    ) -> Result<(), Error>;
+    /// Resize the vector in-place so that `len` is equal to `new_len`.
+    ///
+    /// If `new_len` is greater than `len`, the vector is extended by emplacing initializers returned by `factory`.
+    /// If `new_len` is less than `len`, the vector is truncated.
+    fn resize_emplace<I: Init<Self::Item>>(&mut self, new_len: usize, factory: impl FnMut() -> I);
+
+    /// Like [`try_push_emplace`](VecExt::try_push_emplace), but surfaces allocation failure instead of aborting.
+    fn checked_push_emplace<Error>(
+        &mut self,
+        init: impl Init<Self::Item, Error>,
+    ) -> Result<(), AllocOrInit<Error>>;
+    /// Like [`try_append_emplace`](VecExt::try_append_emplace), but surfaces allocation failure instead of aborting.
+    fn checked_append_emplace<Error>(
+        &mut self,
+        init: impl Init<[Self::Item], Error>,
+    ) -> Result<(), AllocOrInit<Error>>;
+    /// Like [`try_extend_emplace`](VecExt::try_extend_emplace), but surfaces allocation failure instead of aborting.
+    fn checked_extend_emplace<Error>(
+        &mut self,
+        iter: impl IntoIterator<Item: Init<Self::Item, Error>>,
+    ) -> Result<(), AllocOrInit<Error>>;
+    /// Like [`try_insert_emplace`](VecExt::try_insert_emplace), but surfaces allocation failure instead of aborting.
+    fn checked_insert_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<Self::Item, Error>,
+    ) -> Result<(), AllocOrInit<Error>>;
+    /// Like [`try_insert_slice_emplace`](VecExt::try_insert_slice_emplace), but surfaces allocation failure instead of aborting.
+    fn checked_insert_slice_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<[Self::Item], Error>,
+    ) -> Result<(), AllocOrInit<Error>>;
+    /// Like [`try_splice_emplace`](VecExt::try_splice_emplace), but surfaces allocation failure instead of aborting.
+    fn checked_splice_emplace<Error>(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        init: impl Init<[Self::Item], Error>,
+    ) -> Result<(), AllocOrInit<Error>>;
+    /// Like [`try_resize_emplace`](VecExt::try_resize_emplace), but surfaces allocation failure instead of aborting.
+    fn checked_resize_emplace<Error, I: Init<Self::Item, Error>>(
+        &mut self,
+        new_len: usize,
+        factory: impl FnMut() -> I,
+    ) -> Result<(), AllocOrInit<Error>>;
+}
+
+/// Replace `vec[range]` with `additional` elements, which are initialized by `init`.
+///
+/// The elements in `range` are dropped first. If `init` fails or panics, the elements after
+/// `range` are shifted back.
+///
+/// # Safety
+///
+/// If `init` returns `Ok(())`, the slice passed to it must be fully initialized.
+pub(crate) unsafe fn splice_emplace_impl<T, A: Allocator, Error>(
+    vec: &mut Vec<T, A>,
+    range: impl RangeBounds<usize>,
+    additional: usize,
+    init: impl FnOnce(*mut [T]) -> Result<(), Error>,
+) -> Result<(), Error> {
+    use core::ptr;
+
+    let len = vec.len();
+    let core::ops::Range { start, end } = core::slice::range(range, ..len);
+    let tail_len = len - end;
+    vec.reserve(additional.saturating_sub(end - start));
+
+    /// Moves the tail of the vector to be directly after the prefix.
+    struct ShiftBackOnDrop<'a, T, A: Allocator> {
+        vec: &'a mut Vec<T, A>,
+        start: usize,
+        tail: usize,
+        tail_len: usize,
+    }
+    impl<T, A: Allocator> Drop for ShiftBackOnDrop<'_, T, A> {
+        fn drop(&mut self) {
+            unsafe {
+                let base = self.vec.as_mut_ptr();
+                ptr::copy(base.add(self.tail), base.add(self.start), self.tail_len);
+                self.vec.set_len(self.start + self.tail_len);
+            }
+        }
+    }
+
+    unsafe {
+        // Until the guard moves the tail back (or we finish), only the prefix is owned by the vector.
+        vec.set_len(start);
+        let base = vec.as_mut_ptr();
+        let mut guard = ShiftBackOnDrop {
+            vec,
+            start,
+            tail: end,
+            tail_len,
+        };
+
+        ptr::drop_in_place(ptr::slice_from_raw_parts_mut(base.add(start), end - start));
+
+        ptr::copy(base.add(end), base.add(start + additional), tail_len);
+        guard.tail = start + additional;
+
+        // dropping `guard` on error or panic shifts the tail back
+        init(ptr::slice_from_raw_parts_mut(base.add(start), additional))?;
+
+        let mut guard = core::mem::ManuallyDrop::new(guard);
+        guard.vec.set_len(start + additional + tail_len);
+    }
+    Ok(())
+}
+
+/// Append `additional` elements, which are initialized by `init`.
+///
+/// # Safety
+///
+/// `vec` must have capacity for `additional` more elements. If `init` returns `Ok(())`, the slice
+/// passed to it must be fully initialized.
+pub(crate) unsafe fn append_emplace_impl<T, A: Allocator, Error>(
+    vec: &mut Vec<T, A>,
+    additional: usize,
+    init: impl FnOnce(*mut [T]) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let len = vec.len();
+    unsafe {
+        init(&mut vec.spare_capacity_mut()[..additional] as *mut [MaybeUninit<T>] as *mut [T])?;
+        vec.set_len(len + additional);
+    };
+    Ok(())
 }
 
 unsafe impl<T, A: Allocator> VecExt for Vec<T, A> {
@@ -111,15 +319,8 @@ unsafe impl<T, A: Allocator> VecExt for Vec<T, A> {
     fn try_append_emplace<Error>(&mut self, init: impl Init<[T], Error>) -> Result<(), Error> {
         let additional = init.metadata();
         self.reserve(additional);
-        let len = self.len();
-        unsafe {
-            init.init(
-                &mut self.spare_capacity_mut()[..additional] as *mut [MaybeUninit<T>] as *mut [T],
-                (),
-            )?;
-            self.set_len(len + additional);
-        };
-        Ok(())
+        // SAFETY: we reserved `additional` slots, and `dst` has the metadata `init` asked for
+        unsafe { append_emplace_impl(self, additional, |dst| init.init(dst, ())) }
     }
 
     fn append_emplace(&mut self, init: impl Init<[T]>) {
@@ -146,4 +347,158 @@ unsafe impl<T, A: Allocator> VecExt for Vec<T, A> {
     fn extend_emplace(&mut self, iter: impl IntoIterator<Item: Init<T>>) {
         self.try_extend_emplace(iter).unwrap_or_else(|e| match e {});
     }
+
+    fn try_insert_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<T, Error>,
+    ) -> Result<(), Error> {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+        // SAFETY: `init` initializes the single element
+        unsafe { splice_emplace_impl(self, index..index, 1, |dst| init.init(dst.cast::<T>(), ())) }
+    }
+
+    fn insert_emplace(&mut self, index: usize, init: impl Init<T>) {
+        self.try_insert_emplace(index, init)
+            .unwrap_or_else(|e| match e {});
+    }
+
+    fn try_insert_slice_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<[T], Error>,
+    ) -> Result<(), Error> {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+        self.try_splice_emplace(index..index, init)
+    }
+
+    fn insert_slice_emplace(&mut self, index: usize, init: impl Init<[T]>) {
+        self.try_insert_slice_emplace(index, init)
+            .unwrap_or_else(|e| match e {});
+    }
+
+    fn try_splice_emplace<Error>(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        init: impl Init<[T], Error>,
+    ) -> Result<(), Error> {
+        let additional = init.metadata();
+        // SAFETY: `dst` has the metadata `init` asked for
+        unsafe { splice_emplace_impl(self, range, additional, |dst| init.init(dst, ())) }
+    }
+
+    fn splice_emplace(&mut self, range: impl RangeBounds<usize>, init: impl Init<[T]>) {
+        self.try_splice_emplace(range, init)
+            .unwrap_or_else(|e| match e {});
+    }
+
+    fn try_resize_emplace<Error, I: Init<T, Error>>(
+        &mut self,
+        new_len: usize,
+        factory: impl FnMut() -> I,
+    ) -> Result<(), Error> {
+        let len = self.len();
+        if new_len > len {
+            self.try_extend_emplace(core::iter::repeat_with(factory).take(new_len - len))
+        } else {
+            self.truncate(new_len);
+            Ok(())
+        }
+    }
+
+    fn resize_emplace<I: Init<T>>(&mut self, new_len: usize, factory: impl FnMut() -> I) {
+        self.try_resize_emplace(new_len, factory)
+            .unwrap_or_else(|e| match e {});
+    }
+
+    // The `checked_*` methods reserve up front, so the `reserve` calls in the `try_*` methods are no-ops.
+
+    fn checked_push_emplace<Error>(
+        &mut self,
+        init: impl Init<T, Error>,
+    ) -> Result<(), AllocOrInit<Error>> {
+        self.try_reserve(1)?;
+        self.try_push_emplace(init).map_err(AllocOrInit::Init)
+    }
+
+    fn checked_append_emplace<Error>(
+        &mut self,
+        init: impl Init<[T], Error>,
+    ) -> Result<(), AllocOrInit<Error>> {
+        let additional = init.metadata();
+        self.try_reserve(additional)?;
+        // SAFETY: we reserved `additional` slots, and `dst` has the metadata `init` asked for
+        unsafe { append_emplace_impl(self, additional, |dst| init.init(dst, ())) }
+            .map_err(AllocOrInit::Init)
+    }
+
+    fn checked_extend_emplace<Error>(
+        &mut self,
+        iter: impl IntoIterator<Item: Init<T, Error>>,
+    ) -> Result<(), AllocOrInit<Error>> {
+        let iter = iter.into_iter();
+        self.try_reserve(iter.size_hint().0)?;
+        for init in iter {
+            self.checked_push_emplace(init)?;
+        }
+        Ok(())
+    }
+
+    fn checked_insert_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<T, Error>,
+    ) -> Result<(), AllocOrInit<Error>> {
+        self.try_reserve(1)?;
+        self.try_insert_emplace(index, init)
+            .map_err(AllocOrInit::Init)
+    }
+
+    fn checked_insert_slice_emplace<Error>(
+        &mut self,
+        index: usize,
+        init: impl Init<[T], Error>,
+    ) -> Result<(), AllocOrInit<Error>> {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+        let additional = init.metadata();
+        self.try_reserve(additional)?;
+        // SAFETY: `dst` has the metadata `init` asked for
+        unsafe { splice_emplace_impl(self, index..index, additional, |dst| init.init(dst, ())) }
+            .map_err(AllocOrInit::Init)
+    }
+
+    fn checked_splice_emplace<Error>(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        init: impl Init<[T], Error>,
+    ) -> Result<(), AllocOrInit<Error>> {
+        let range = core::slice::range(range, ..self.len());
+        let additional = init.metadata();
+        self.try_reserve(additional.saturating_sub(range.len()))?;
+        // SAFETY: `dst` has the metadata `init` asked for
+        unsafe { splice_emplace_impl(self, range, additional, |dst| init.init(dst, ())) }
+            .map_err(AllocOrInit::Init)
+    }
+
+    fn checked_resize_emplace<Error, I: Init<T, Error>>(
+        &mut self,
+        new_len: usize,
+        factory: impl FnMut() -> I,
+    ) -> Result<(), AllocOrInit<Error>> {
+        self.try_reserve(new_len.saturating_sub(self.len()))?;
+        self.try_resize_emplace(new_len, factory)
+            .map_err(AllocOrInit::Init)
+    }
 }
@@ -0,0 +1,166 @@
+use core::{mem::MaybeUninit, ptr};
+
+use alloc::{alloc::Allocator, boxed::Box, collections::VecDeque};
+
+use crate::Init;
+
+/// View a `VecDeque<T, A>` as a `VecDeque<MaybeUninit<T>, A>`, so that uninitialized slots can be
+/// pushed and then initialized in place.
+///
+/// NOTE: this relies on `VecDeque<T, A>` and `VecDeque<MaybeUninit<T>, A>` having the same layout,
+/// which is not guaranteed (hence the `assume-vec-deque-layout` feature).
+///
+/// # Safety
+///
+/// Any slots pushed through the returned reference must be initialized or removed again before
+/// `deque` is used as a `VecDeque<T, A>`.
+unsafe fn as_uninit<T, A: Allocator>(
+    deque: &mut VecDeque<T, A>,
+) -> &mut VecDeque<MaybeUninit<T>, A> {
+    unsafe { &mut *(deque as *mut VecDeque<T, A>).cast::<VecDeque<MaybeUninit<T>, A>>() }
+}
+
+/// Removes the not-yet-initialized slots past `len` if emplacing fails or panics.
+struct TruncateOnDrop<'a, T, A: Allocator> {
+    deque: &'a mut VecDeque<MaybeUninit<T>, A>,
+    len: usize,
+}
+
+impl<T, A: Allocator> Drop for TruncateOnDrop<'_, T, A> {
+    fn drop(&mut self) {
+        self.deque.truncate(self.len);
+    }
+}
+
+/// Removes the not-yet-initialized slot at the front if emplacing fails or panics.
+struct PopFrontOnDrop<'a, T, A: Allocator> {
+    deque: &'a mut VecDeque<MaybeUninit<T>, A>,
+}
+
+impl<T, A: Allocator> Drop for PopFrontOnDrop<'_, T, A> {
+    fn drop(&mut self) {
+        self.deque.pop_front();
+    }
+}
+
+pub trait VecDequeExt {
+    type Item;
+
+    /// Emplace an element at the back of the deque.
+    ///
+    /// If `init` fails or panics, the deque is left unchanged (though it may have grown its capacity).
+    fn try_push_back_emplace<Error>(
+        &mut self,
+        init: impl Init<Self::Item, Error>,
+    ) -> Result<(), Error>;
+    /// Emplace an element at the back of the deque.
+    fn push_back_emplace(&mut self, init: impl Init<Self::Item>);
+
+    /// Emplace an element at the front of the deque.
+    ///
+    /// If `init` fails or panics, the deque is left unchanged (though it may have grown its capacity).
+    fn try_push_front_emplace<Error>(
+        &mut self,
+        init: impl Init<Self::Item, Error>,
+    ) -> Result<(), Error>;
+    /// Emplace an element at the front of the deque.
+    fn push_front_emplace(&mut self, init: impl Init<Self::Item>);
+
+    /// Emplace a slice of elements at the back of the deque.
+    ///
+    /// The new elements are initialized in place, unless they would wrap around the end of the
+    /// ring buffer. Since `init` needs a contiguous destination, they are then initialized in a
+    /// temporary allocation, and moved into the two segments of the ring buffer.
+    ///
+    /// If `init` fails or panics, the deque is left unchanged (though it may have grown its capacity).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    fn try_append_emplace<Error>(
+        &mut self,
+        init: impl Init<[Self::Item], Error>,
+    ) -> Result<(), Error>;
+    /// Emplace a slice of elements at the back of the deque.
+    ///
+    /// See [`try_append_emplace`](VecDequeExt::try_append_emplace).
+    fn append_emplace(&mut self, init: impl Init<[Self::Item]>);
+}
+
+impl<T, A: Allocator> VecDequeExt for VecDeque<T, A> {
+    type Item = T;
+
+    fn try_push_back_emplace<Error>(&mut self, init: impl Init<T, Error>) -> Result<(), Error> {
+        let len = self.len();
+        // SAFETY: the pushed slot is initialized below, or removed by `guard`
+        let uninit = unsafe { as_uninit(self) };
+        uninit.push_back(MaybeUninit::uninit());
+        let guard = TruncateOnDrop { deque: uninit, len };
+        crate::try_initialize(&mut guard.deque[len], init)?;
+        core::mem::forget(guard);
+        Ok(())
+    }
+
+    fn push_back_emplace(&mut self, init: impl Init<T>) {
+        self.try_push_back_emplace(init)
+            .unwrap_or_else(|e| match e {});
+    }
+
+    fn try_push_front_emplace<Error>(&mut self, init: impl Init<T, Error>) -> Result<(), Error> {
+        // SAFETY: the pushed slot is initialized below, or removed by `guard`
+        let uninit = unsafe { as_uninit(self) };
+        uninit.push_front(MaybeUninit::uninit());
+        let guard = PopFrontOnDrop { deque: uninit };
+        crate::try_initialize(&mut guard.deque[0], init)?;
+        core::mem::forget(guard);
+        Ok(())
+    }
+
+    fn push_front_emplace(&mut self, init: impl Init<T>) {
+        self.try_push_front_emplace(init)
+            .unwrap_or_else(|e| match e {});
+    }
+
+    fn try_append_emplace<Error>(&mut self, init: impl Init<[T], Error>) -> Result<(), Error> {
+        let additional = init.metadata();
+        let len = self.len();
+        // This panics on capacity overflow, so `len + additional` cannot overflow below.
+        self.reserve(additional);
+        // SAFETY: the pushed slots are initialized below, or removed by `guard`
+        let uninit = unsafe { as_uninit(self) };
+        uninit.resize_with(len + additional, MaybeUninit::uninit);
+        let guard = TruncateOnDrop { deque: uninit, len };
+
+        // The new slots are either entirely in the first ring segment, entirely in the second,
+        // or the end of the first followed by all of the second.
+        let (front, back) = guard.deque.as_mut_slices();
+        if front.len() <= len {
+            let dst: *mut [MaybeUninit<T>] = &mut back[len - front.len()..];
+            unsafe { init.init(dst as *mut [T], ())? };
+        } else if back.is_empty() {
+            let dst: *mut [MaybeUninit<T>] = &mut front[len..];
+            unsafe { init.init(dst as *mut [T], ())? };
+        } else {
+            let (first, second): (*mut [MaybeUninit<T>], *mut [MaybeUninit<T>]) =
+                (&mut front[len..], back);
+            let temp = crate::try_new_boxed_in::<[T], _, _>(init, guard.deque.allocator())?;
+            let (temp, alloc) = Box::into_raw_with_allocator(temp);
+            let temp = temp as *mut [MaybeUninit<T>];
+            // SAFETY: `temp` holds `first.len() + second.len()` elements, which are moved out
+            // before freeing it, and it does not overlap with the deque's buffer
+            unsafe {
+                let src = temp.cast::<MaybeUninit<T>>();
+                ptr::copy_nonoverlapping(src, first.cast(), first.len());
+                ptr::copy_nonoverlapping(src.add(first.len()), second.cast(), second.len());
+                drop(Box::from_raw_in(temp, alloc));
+            }
+        }
+
+        core::mem::forget(guard);
+        Ok(())
+    }
+
+    fn append_emplace(&mut self, init: impl Init<[T]>) {
+        self.try_append_emplace(init).unwrap_or_else(|e| match e {});
+    }
+}
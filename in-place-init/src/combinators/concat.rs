@@ -0,0 +1,265 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{Init, PinInit, util::Written};
+
+/// Initialize a slice, `str` or array from a tuple of pieces, one after another.
+///
+/// ```rust
+/// let bx: Box<[u8]> = in_place_init::new_boxed(in_place_init::concat((
+///     [1, 2],
+///     in_place_init::slice_repeat(3, 0),
+///     &[3u8][..],
+/// )));
+/// assert_eq!(*bx, [1, 2, 0, 0, 0, 3]);
+///
+/// let bx: Box<str> = in_place_init::new_boxed(in_place_init::concat(("a", "bc", "def")));
+/// assert_eq!(&*bx, "abcdef");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Concat<Pieces> {
+    pieces: Pieces,
+}
+
+impl<Pieces> Concat<Pieces> {
+    pub fn new(pieces: Pieces) -> Self {
+        Self { pieces }
+    }
+}
+
+/// Initialize an array from a tuple of array pieces, whose lengths must add up to `P`.
+///
+/// `A` is the tuple of the pieces' array types.
+///
+/// ```rust
+/// let bx: Box<[u8; 5]> = in_place_init::new_boxed(in_place_init::concat_array((
+///     [1, 2],
+///     in_place_init::array_repeat::<3, _>(0),
+/// )));
+/// assert_eq!(*bx, [1, 2, 0, 0, 0]);
+/// ```
+pub struct ConcatArray<A, const P: usize, Pieces> {
+    arrays: PhantomData<fn() -> A>,
+    pieces: Pieces,
+}
+
+impl<A, const P: usize, Pieces: Clone> Clone for ConcatArray<A, P, Pieces> {
+    fn clone(&self) -> Self {
+        Self {
+            arrays: PhantomData,
+            pieces: self.pieces.clone(),
+        }
+    }
+}
+impl<A, const P: usize, Pieces: Copy> Copy for ConcatArray<A, P, Pieces> {}
+
+impl<A, const P: usize, Pieces> ConcatArray<A, P, Pieces> {
+    pub fn new(pieces: Pieces) -> Self {
+        Self {
+            arrays: PhantomData,
+            pieces,
+        }
+    }
+}
+
+macro_rules! concat_tuple {
+    ($($init:ident $idx:tt $n:ident),+) => {
+        unsafe impl<T, Error, Extra: Clone, $($init: PinInit<[T], Error, Extra>),+>
+            PinInit<[T], Error, Extra> for Concat<($($init,)+)>
+        {
+            fn metadata(&self) -> usize {
+                let len = 0usize;
+                $(
+                    let len = len
+                        .checked_add(self.pieces.$idx.metadata())
+                        .expect("slice length overflow");
+                )+
+                len
+            }
+
+            unsafe fn init(self, dst: *mut [T], extra: Extra) -> Result<(), Error> {
+                let mut written = Written::new(dst.cast::<T>());
+                $(
+                    let len = self.pieces.$idx.metadata();
+                    // SAFETY: the pieces' lengths add up to `dst.len()`
+                    let piece = unsafe { written.next(len) };
+                    unsafe { self.pieces.$idx.init(piece, extra.clone()) }?;
+                    written.len += len;
+                )+
+                debug_assert_eq!(written.len, dst.len());
+                core::mem::forget(written);
+                Ok(())
+            }
+        }
+        unsafe impl<T, Error, Extra: Clone, $($init: Init<[T], Error, Extra>),+>
+            Init<[T], Error, Extra> for Concat<($($init,)+)>
+        {
+        }
+
+        unsafe impl<Error, Extra: Clone, $($init: PinInit<str, Error, Extra>),+>
+            PinInit<str, Error, Extra> for Concat<($($init,)+)>
+        {
+            fn metadata(&self) -> usize {
+                let len = 0usize;
+                $(
+                    let len = len
+                        .checked_add(self.pieces.$idx.metadata())
+                        .expect("slice length overflow");
+                )+
+                len
+            }
+
+            unsafe fn init(self, dst: *mut str, extra: Extra) -> Result<(), Error> {
+                let dst = dst as *mut [u8];
+                // `u8` has no destructor, so nothing needs to be dropped on failure.
+                let mut written = Written::new(dst.cast::<u8>());
+                $(
+                    let len = self.pieces.$idx.metadata();
+                    // SAFETY: the pieces' lengths add up to `dst.len()`
+                    let piece = unsafe { written.next(len) } as *mut str;
+                    unsafe { self.pieces.$idx.init(piece, extra.clone()) }?;
+                    written.len += len;
+                )+
+                debug_assert_eq!(written.len, dst.len());
+                Ok(())
+            }
+        }
+        unsafe impl<Error, Extra: Clone, $($init: Init<str, Error, Extra>),+>
+            Init<str, Error, Extra> for Concat<($($init,)+)>
+        {
+        }
+
+        unsafe impl<
+            T,
+            Error,
+            Extra: Clone,
+            const P: usize,
+            $(const $n: usize, $init: PinInit<[T; $n], Error, Extra>,)+
+        > PinInit<[T; P], Error, Extra> for ConcatArray<($([T; $n],)+), P, ($($init,)+)>
+        {
+            fn metadata(&self) {}
+
+            unsafe fn init(self, dst: *mut [T; P], extra: Extra) -> Result<(), Error> {
+                const {
+                    assert!(0 $(+ $n)+ == P, "array length mismatch");
+                }
+                let mut written = Written::new(dst.cast::<T>());
+                $(
+                    // SAFETY: the pieces' lengths add up to `P`
+                    let piece = unsafe { written.next($n) }.cast::<[T; $n]>();
+                    unsafe { self.pieces.$idx.init(piece, extra.clone()) }?;
+                    written.len += $n;
+                )+
+                core::mem::forget(written);
+                Ok(())
+            }
+        }
+        unsafe impl<
+            T,
+            Error,
+            Extra: Clone,
+            const P: usize,
+            $(const $n: usize, $init: Init<[T; $n], Error, Extra>,)+
+        > Init<[T; P], Error, Extra> for ConcatArray<($([T; $n],)+), P, ($($init,)+)>
+        {
+        }
+    };
+}
+
+concat_tuple!(I0 0 N0);
+concat_tuple!(I0 0 N0, I1 1 N1);
+concat_tuple!(I0 0 N0, I1 1 N1, I2 2 N2);
+concat_tuple!(I0 0 N0, I1 1 N1, I2 2 N2, I3 3 N3);
+concat_tuple!(I0 0 N0, I1 1 N1, I2 2 N2, I3 3 N3, I4 4 N4);
+concat_tuple!(I0 0 N0, I1 1 N1, I2 2 N2, I3 3 N3, I4 4 N4, I5 5 N5);
+concat_tuple!(I0 0 N0, I1 1 N1, I2 2 N2, I3 3 N3, I4 4 N4, I5 5 N5, I6 6 N6);
+concat_tuple!(I0 0 N0, I1 1 N1, I2 2 N2, I3 3 N3, I4 4 N4, I5 5 N5, I6 6 N6, I7 7 N7);
+concat_tuple!(I0 0 N0, I1 1 N1, I2 2 N2, I3 3 N3, I4 4 N4, I5 5 N5, I6 6 N6, I7 7 N7, I8 8 N8);
+concat_tuple!(
+    I0 0 N0, I1 1 N1, I2 2 N2, I3 3 N3, I4 4 N4, I5 5 N5, I6 6 N6, I7 7 N7, I8 8 N8, I9 9 N9
+);
+concat_tuple!(
+    I0 0 N0, I1 1 N1, I2 2 N2, I3 3 N3, I4 4 N4, I5 5 N5, I6 6 N6, I7 7 N7, I8 8 N8, I9 9 N9,
+    I10 10 N10
+);
+concat_tuple!(
+    I0 0 N0, I1 1 N1, I2 2 N2, I3 3 N3, I4 4 N4, I5 5 N5, I6 6 N6, I7 7 N7, I8 8 N8, I9 9 N9,
+    I10 10 N10, I11 11 N11
+);
+
+/// Initialize a slice or `str` from a runtime-length list of pieces, one after another.
+///
+/// ```rust
+/// let words = ["in", "-", "place"];
+/// let bx: Box<str> = in_place_init::new_boxed(in_place_init::concat_iter(words));
+/// assert_eq!(&*bx, "in-place");
+/// ```
+#[derive(Clone)]
+pub struct ConcatIter<I> {
+    pieces: Vec<I>,
+}
+
+impl<I> ConcatIter<I> {
+    pub fn new(pieces: Vec<I>) -> Self {
+        Self { pieces }
+    }
+}
+
+unsafe impl<T, Error, Extra: Clone, I: PinInit<[T], Error, Extra>> PinInit<[T], Error, Extra>
+    for ConcatIter<I>
+{
+    fn metadata(&self) -> usize {
+        self.pieces.iter().fold(0usize, |len, piece| {
+            len.checked_add(piece.metadata())
+                .expect("slice length overflow")
+        })
+    }
+
+    unsafe fn init(self, dst: *mut [T], extra: Extra) -> Result<(), Error> {
+        let mut written = Written::new(dst.cast::<T>());
+        for piece in self.pieces {
+            let len = piece.metadata();
+            // SAFETY: the pieces' lengths add up to `dst.len()`
+            let dst = unsafe { written.next(len) };
+            unsafe { piece.init(dst, extra.clone()) }?;
+            written.len += len;
+        }
+        debug_assert_eq!(written.len, dst.len());
+        core::mem::forget(written);
+        Ok(())
+    }
+}
+unsafe impl<T, Error, Extra: Clone, I: Init<[T], Error, Extra>> Init<[T], Error, Extra>
+    for ConcatIter<I>
+{
+}
+
+unsafe impl<Error, Extra: Clone, I: PinInit<str, Error, Extra>> PinInit<str, Error, Extra>
+    for ConcatIter<I>
+{
+    fn metadata(&self) -> usize {
+        self.pieces.iter().fold(0usize, |len, piece| {
+            len.checked_add(piece.metadata())
+                .expect("slice length overflow")
+        })
+    }
+
+    unsafe fn init(self, dst: *mut str, extra: Extra) -> Result<(), Error> {
+        let dst = dst as *mut [u8];
+        // `u8` has no destructor, so nothing needs to be dropped on failure.
+        let mut written = Written::new(dst.cast::<u8>());
+        for piece in self.pieces {
+            let len = piece.metadata();
+            // SAFETY: the pieces' lengths add up to `dst.len()`
+            let dst = unsafe { written.next(len) } as *mut str;
+            unsafe { piece.init(dst, extra.clone()) }?;
+            written.len += len;
+        }
+        debug_assert_eq!(written.len, dst.len());
+        Ok(())
+    }
+}
+unsafe impl<Error, Extra: Clone, I: Init<str, Error, Extra>> Init<str, Error, Extra>
+    for ConcatIter<I>
+{
+}
@@ -0,0 +1,133 @@
+use core::{
+    marker::{MetaSized, PhantomData},
+    ptr::Pointee,
+};
+
+use crate::{Init, PinInit};
+
+macro_rules! either_n {
+    (
+        $(#[$attr:meta])*
+        $name:ident($inner:ident); $($var:ident $ctor:ident),*
+    ) => {
+        #[derive(Clone, Copy)]
+        enum $inner<$($var),*> {
+            $($var($var),)*
+        }
+
+        $(#[$attr])*
+        pub struct $name<T: MetaSized, $($var),*> {
+            /// We need to mention `T` so the compiler knows this can't overlap with `impl Init<T> for T`.
+            result: PhantomData<fn() -> T>,
+            inner: $inner<$($var),*>,
+        }
+
+        impl<T: MetaSized, $($var: Clone),*> Clone for $name<T, $($var),*> {
+            fn clone(&self) -> Self {
+                Self {
+                    result: PhantomData,
+                    inner: self.inner.clone(),
+                }
+            }
+        }
+
+        impl<T: MetaSized, $($var: Copy),*> Copy for $name<T, $($var),*> {}
+
+        impl<T: MetaSized, $($var),*> $name<T, $($var),*> {
+            $(
+                #[doc = concat!("Initialize with the `", stringify!($var), "` initializer.")]
+                pub fn $ctor(init: $var) -> Self {
+                    Self {
+                        result: PhantomData,
+                        inner: $inner::$var(init),
+                    }
+                }
+            )*
+        }
+
+        unsafe impl<T: MetaSized, Error, Extra, $($var: PinInit<T, Error, Extra>),*>
+            PinInit<T, Error, Extra> for $name<T, $($var),*>
+        {
+            fn metadata(&self) -> <T as Pointee>::Metadata {
+                match &self.inner {
+                    $($inner::$var(init) => init.metadata(),)*
+                }
+            }
+
+            unsafe fn init(self, dst: *mut T, extra: Extra) -> Result<(), Error> {
+                // SAFETY: discharged to caller
+                match self.inner {
+                    $($inner::$var(init) => unsafe { init.init(dst, extra) },)*
+                }
+            }
+        }
+        unsafe impl<T: MetaSized, Error, Extra, $($var: Init<T, Error, Extra>),*>
+            Init<T, Error, Extra> for $name<T, $($var),*>
+        {
+        }
+    };
+}
+
+either_n!(
+    /// Initialize with one of two initializers, chosen at runtime.
+    ///
+    /// ```rust
+    /// # use in_place_init::Either;
+    /// let short = true;
+    /// let bx: Box<[u8]> = in_place_init::new_boxed(if short {
+    ///     Either::left([1, 2])
+    /// } else {
+    ///     Either::right(in_place_init::slice_repeat(1024, 0))
+    /// });
+    /// assert_eq!(*bx, [1, 2]);
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Point {
+    ///     x: u32,
+    ///     y: u32,
+    /// }
+    /// let origin = false;
+    /// let bx: Box<Point> = in_place_init::new_boxed(if origin {
+    ///     Either::left(Point { x: 0, y: 0 })
+    /// } else {
+    ///     Either::right(in_place_init::with(|()| Point { x: 1, y: 2 }))
+    /// });
+    /// assert_eq!(*bx, Point { x: 1, y: 2 });
+    /// ```
+    Either(EitherInner); L left, R right
+);
+either_n!(
+    /// Initialize with one of three initializers, chosen at runtime, e.g. by a `match`.
+    ///
+    /// ```rust
+    /// # use in_place_init::Either3;
+    /// let n = 2;
+    /// let bx: Box<str> = in_place_init::new_boxed(match n {
+    ///     0 => Either3::a("zero"),
+    ///     1 => Either3::b(in_place_init::chain("o", "ne")),
+    ///     _ => Either3::c(Box::<str>::from("many")),
+    /// });
+    /// assert_eq!(&*bx, "many");
+    /// ```
+    Either3(Either3Inner); A a, B b, C c
+);
+either_n!(
+    /// Initialize with one of four initializers, chosen at runtime. See [`Either3`].
+    Either4(Either4Inner); A a, B b, C c, D d
+);
+either_n!(
+    /// Initialize with one of five initializers, chosen at runtime. See [`Either3`].
+    Either5(Either5Inner); A a, B b, C c, D d, E e
+);
+either_n!(
+    /// Initialize with one of six initializers, chosen at runtime. See [`Either3`].
+    Either6(Either6Inner); A a, B b, C c, D d, E e, F f
+);
+either_n!(
+    /// Initialize with one of seven initializers, chosen at runtime. See [`Either3`].
+    Either7(Either7Inner); A a, B b, C c, D d, E e, F f, G g
+);
+either_n!(
+    /// Initialize with one of eight initializers, chosen at runtime. See [`Either3`].
+    Either8(Either8Inner); A a, B b, C c, D d, E e, F f, G g, H h
+);
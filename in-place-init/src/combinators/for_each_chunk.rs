@@ -0,0 +1,188 @@
+use core::ops::Range;
+
+use crate::{
+    Init, MetadataMismatch, PinInit,
+    util::{ConstLength, Length, RuntimeLength, Written},
+};
+
+/// Initialize an array or slice by creating a slice initializer for each chunk of it.
+///
+/// Each chunk has `chunk_length` elements, except the last one, which may be shorter. The
+/// function is given the range of each chunk, and the initializer it returns must have the
+/// same length as the range; otherwise [`MetadataMismatch`] is returned (or, for infallible
+/// initializers, panicked with).
+///
+/// ```rust
+/// let frames = [[1u8, 2], [3, 4], [5, 6]];
+/// let bx: Box<[u8]> = in_place_init::new_boxed(in_place_init::slice_for_each_chunk(
+///     5,
+///     2,
+///     |range: std::ops::Range<usize>| &frames[range.start / 2][..range.len()],
+/// ));
+/// assert_eq!(*bx, [1, 2, 3, 4, 5]);
+///
+/// let bx: Box<[u32; 6]> = in_place_init::new_boxed(
+///     in_place_init::array_for_each_chunk::<6, 4, _>(|range: std::ops::Range<usize>| {
+///         in_place_init::slice_repeat(range.len(), range.start as u32)
+///     }),
+/// );
+/// assert_eq!(*bx, [0, 0, 0, 0, 4, 4]);
+/// ```
+#[derive(Clone)]
+pub struct ForEachChunk<F, L: Length, C: Length> {
+    length: L,
+    chunk_length: C,
+    func: F,
+}
+
+impl<F> ForEachChunk<F, RuntimeLength, RuntimeLength> {
+    /// Initialize a slice of `length` elements, in chunks of `chunk_length` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_length` is zero and `length` is not.
+    pub fn new_slice(length: usize, chunk_length: usize, func: F) -> Self {
+        assert!(
+            chunk_length != 0 || length == 0,
+            "chunk length must be nonzero"
+        );
+        Self {
+            length: RuntimeLength { length },
+            chunk_length: RuntimeLength {
+                length: chunk_length,
+            },
+            func,
+        }
+    }
+}
+
+impl<F, const N: usize, const C: usize> ForEachChunk<F, ConstLength<N>, ConstLength<C>> {
+    /// Initialize an array of `N` elements, in chunks of `C` elements.
+    ///
+    /// Fails to compile if `C` is zero and `N` is not.
+    pub fn new_array(func: F) -> Self {
+        const {
+            assert!(C != 0 || N == 0, "chunk length must be nonzero");
+        }
+        Self {
+            length: ConstLength,
+            chunk_length: ConstLength,
+            func,
+        }
+    }
+}
+
+/// Initialize `dst` chunk by chunk, calling `mismatch` if an initializer has the wrong length.
+///
+/// # Safety
+///
+/// See [`PinInit::init`].
+unsafe fn init_chunks<
+    T,
+    L: Length,
+    C: Length,
+    Error,
+    Extra: Clone,
+    I: PinInit<[T], Error, Extra>,
+>(
+    mut chunks: ForEachChunk<impl FnMut(Range<usize>) -> I, L, C>,
+    dst: *mut [T],
+    extra: Extra,
+    mismatch: impl FnOnce() -> Error,
+) -> Result<(), Error> {
+    let length = chunks.length.length();
+    let chunk_length = chunks.chunk_length.length();
+    debug_assert_eq!(dst.len(), length);
+    let mut written = Written::new(dst.cast::<T>());
+    while written.len < length {
+        let start = written.len;
+        let end = start.saturating_add(chunk_length).min(length);
+        let init = (chunks.func)(start..end);
+        if init.metadata() != end - start {
+            return Err(mismatch());
+        }
+        // SAFETY: `end <= dst.len()`
+        let chunk = unsafe { written.next(end - start) };
+        // SAFETY: discharged to caller, and the chunk has the initializer's length
+        unsafe { init.init(chunk, extra.clone()) }?;
+        written.len = end;
+    }
+    core::mem::forget(written);
+    Ok(())
+}
+
+// A mismatched chunk is returned as an error if `Error: From<MetadataMismatch>`, and panicked
+// with if the chunk initializers are infallible.
+macro_rules! chunk_impls {
+    ([$($egen:ident)?] $error:ty, [$($bound:tt)*], $mismatch:expr) => {
+        unsafe impl<
+            T,
+            L: Length,
+            C: Length,
+            $($egen,)?
+            Extra: Clone,
+            I: PinInit<[T], $error, Extra>,
+            F: FnMut(Range<usize>) -> I,
+        > PinInit<[T], $error, Extra> for ForEachChunk<F, L, C>
+        where
+            $($bound)*
+        {
+            fn metadata(&self) -> usize {
+                self.length.length()
+            }
+
+            unsafe fn init(self, dst: *mut [T], extra: Extra) -> Result<(), $error> {
+                // SAFETY: discharged to caller
+                unsafe { init_chunks(self, dst, extra, $mismatch) }
+            }
+        }
+        unsafe impl<
+            T,
+            L: Length,
+            C: Length,
+            $($egen,)?
+            Extra: Clone,
+            I: Init<[T], $error, Extra>,
+            F: FnMut(Range<usize>) -> I,
+        > Init<[T], $error, Extra> for ForEachChunk<F, L, C>
+        where
+            $($bound)*
+        {
+        }
+
+        unsafe impl<
+            T,
+            const N: usize,
+            C: Length,
+            $($egen,)?
+            Extra: Clone,
+            I: PinInit<[T], $error, Extra>,
+            F: FnMut(Range<usize>) -> I,
+        > PinInit<[T; N], $error, Extra> for ForEachChunk<F, ConstLength<N>, C>
+        where
+            $($bound)*
+        {
+            fn metadata(&self) {}
+
+            unsafe fn init(self, dst: *mut [T; N], extra: Extra) -> Result<(), $error> {
+                unsafe { <Self as PinInit<[T], $error, Extra>>::init(self, dst, extra) }
+            }
+        }
+        unsafe impl<
+            T,
+            const N: usize,
+            C: Length,
+            $($egen,)?
+            Extra: Clone,
+            I: Init<[T], $error, Extra>,
+            F: FnMut(Range<usize>) -> I,
+        > Init<[T; N], $error, Extra> for ForEachChunk<F, ConstLength<N>, C>
+        where
+            $($bound)*
+        {
+        }
+    };
+}
+
+chunk_impls!([Error] Error, [Error: From<MetadataMismatch>], || MetadataMismatch.into());
+chunk_impls!([] !, [], || panic!("{MetadataMismatch}"));
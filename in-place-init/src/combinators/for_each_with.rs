@@ -11,7 +11,11 @@ use crate::{
 /// This is similar to using [`With`](crate::With) within [`ForEach`](crate::ForEach),
 /// but has better interactions with borrowing in some cases.
 ///
-/// ```rust
+/// The examples below emplace cyclic slices into an `Rc`, which needs the `assume-rc-layout`
+/// feature (see [`RcCyclicDst`](crate::RcCyclicDst)).
+///
+#[cfg_attr(feature = "assume-rc-layout", doc = "```rust")]
+#[cfg_attr(not(feature = "assume-rc-layout"), doc = "```rust,ignore")]
 /// # use std::rc::{Weak, Rc};
 /// #[derive(Debug)]
 /// struct Foo {
@@ -32,7 +36,8 @@ use crate::{
 /// assert!(Rc::ptr_eq(&rc, &rc2));
 /// ```
 ///
-/// ```rust
+#[cfg_attr(feature = "assume-rc-layout", doc = "```rust")]
+#[cfg_attr(not(feature = "assume-rc-layout"), doc = "```rust,ignore")]
 /// # use std::rc::{Weak, Rc};
 /// # type Token = usize;
 /// #[derive(Debug)]
@@ -60,7 +65,8 @@ use crate::{
 /// assert!(Rc::ptr_eq(&rc, &rc2));
 /// ```
 ///
-/// ```rust,compile_fail
+#[cfg_attr(feature = "assume-rc-layout", doc = "```rust,compile_fail")]
+#[cfg_attr(not(feature = "assume-rc-layout"), doc = "```rust,ignore")]
 /// # use std::rc::{Weak, Rc};
 /// # type Token = usize;
 /// # #[derive(Debug)]
@@ -0,0 +1,84 @@
+use core::fmt::{self, Display, Write};
+
+use crate::{Init, PinInit};
+
+/// Initialize a `str` with the output of formatting a value, e.g. [`format_args!`].
+///
+/// The value is formatted twice: once in [`FormatStr::new`] to measure its length,
+/// and once during initialization to write it directly into the destination.
+#[derive(Clone, Copy)]
+pub struct FormatStr<D> {
+    value: D,
+    len: usize,
+}
+
+impl<D: Display> FormatStr<D> {
+    /// # Panics
+    ///
+    /// Panics if `value`'s `Display` impl returns an error.
+    pub fn new(value: D) -> Self {
+        let mut counter = LengthCounter(0);
+        write!(counter, "{value}")
+            .expect("a formatting trait implementation returned an error when measuring");
+        Self {
+            value,
+            len: counter.0,
+        }
+    }
+}
+
+struct LengthCounter(usize);
+
+impl Write for LengthCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 = self.0.checked_add(s.len()).ok_or(fmt::Error)?;
+        Ok(())
+    }
+}
+
+/// Writes into a (possibly uninitialized) byte buffer, refusing to write past its end.
+struct BufWriter {
+    dst: *mut u8,
+    len: usize,
+    written: usize,
+}
+
+impl Write for BufWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.len() > self.len - self.written {
+            return Err(fmt::Error);
+        }
+        // SAFETY: `dst` is valid for writes of `len` bytes, and we checked there is enough room
+        unsafe {
+            core::ptr::copy_nonoverlapping(s.as_ptr(), self.dst.add(self.written), s.len());
+        }
+        self.written += s.len();
+        Ok(())
+    }
+}
+
+/// Initialization panics if the value's `Display` impl returns an error, or produces
+/// a different length than when it was measured. In either case, no `str` is produced.
+unsafe impl<D: Display, Error> PinInit<str, Error> for FormatStr<D> {
+    fn metadata(&self) -> usize {
+        self.len
+    }
+
+    unsafe fn init(self, dst: *mut str, _: ()) -> Result<(), Error> {
+        debug_assert_eq!((dst as *mut [u8]).len(), self.len);
+        let mut writer = BufWriter {
+            dst: dst.cast::<u8>(),
+            len: self.len,
+            written: 0,
+        };
+        let result = write!(writer, "{}", self.value);
+        // Only whole `&str`s are written, so if exactly `len` bytes were written, `*dst` is valid UTF-8.
+        if result.is_err() || writer.written != self.len {
+            panic!(
+                "a formatting trait implementation returned an error or a different length than when measuring"
+            );
+        }
+        Ok(())
+    }
+}
+unsafe impl<D: Display, Error> Init<str, Error> for FormatStr<D> {}
@@ -0,0 +1,328 @@
+use core::{fmt, iter::TrustedLen};
+
+use crate::{Init, PinInit, VecExt};
+
+/// The error when an iterator does not yield exactly as many items as it reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitFromIterError {
+    /// The iterator ran out of items before the slice was filled.
+    TooShort,
+    /// The iterator still had items after the slice was filled.
+    TooLong,
+}
+
+impl fmt::Display for InitFromIterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InitFromIterError::TooShort => {
+                f.write_str("iterator yielded fewer items than its length")
+            }
+            InitFromIterError::TooLong => {
+                f.write_str("iterator yielded more items than its length")
+            }
+        }
+    }
+}
+
+impl core::error::Error for InitFromIterError {}
+
+/// The error of [`fill`] for [`UnwrapLen`]: either an item failed, or the iterator had the wrong
+/// number of items.
+enum FillError<E> {
+    Item(E),
+    Len(InitFromIterError),
+}
+
+impl<E> From<InitFromIterError> for FillError<E> {
+    fn from(err: InitFromIterError) -> Self {
+        FillError::Len(err)
+    }
+}
+
+impl<E> FillError<E> {
+    #[track_caller]
+    fn unwrap_len(self) -> E {
+        match self {
+            FillError::Item(err) => err,
+            FillError::Len(err) => panic!("{err}"),
+        }
+    }
+}
+
+/// What to do about an iterator that does not yield exactly `len` items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Fail with [`InitFromIterError`].
+    Exact,
+    /// Fail with [`InitFromIterError::TooShort`], but ignore any items after the first `len`.
+    Truncate,
+    /// The iterator is known to yield exactly `len` items, so any items after the first `len` are
+    /// not checked for. Still fails with [`InitFromIterError::TooShort`] if it runs out early,
+    /// since a clone of the iterator may not keep that promise.
+    Trusted,
+}
+
+/// Fill `dst` with the items of `iter`, which are initializers.
+///
+/// # Safety
+///
+/// See [`PinInit::init`].
+unsafe fn fill<T, Error: From<InitFromIterError>, J: PinInit<T, Error>>(
+    dst: *mut [T],
+    mode: Mode,
+    mut iter: impl Iterator<Item = J>,
+) -> Result<(), Error> {
+    let mut buf = unsafe { noop_allocator::owning_slice::empty_from_raw(dst) };
+    let count = buf.capacity();
+    while buf.len() < count {
+        let init = match iter.next() {
+            Some(init) => init,
+            None => return Err(InitFromIterError::TooShort.into()),
+        };
+        // SAFETY: either `J: Init`, or we treat the destination as pinned
+        let init = unsafe { crate::assert_pinned(init) };
+        // SAFETY: there is excess capacity
+        unsafe { buf.try_push_emplace_within_capacity_unchecked(init) }?;
+    }
+    if mode == Mode::Exact && iter.next().is_some() {
+        return Err(InitFromIterError::TooLong.into());
+    }
+    core::mem::forget(buf);
+    Ok(())
+}
+
+macro_rules! from_iter_constructors {
+    ($name:ident) => {
+        impl<I: Iterator> $name<I> {
+            /// Fails if `iter` does not yield exactly `iter.len()` items.
+            pub fn new(iter: I) -> Self
+            where
+                I: ExactSizeIterator,
+            {
+                Self {
+                    len: iter.len(),
+                    mode: Mode::Exact,
+                    iter,
+                }
+            }
+
+            /// Fails if `iter` yields fewer than `iter.len()` items, and ignores any extra items.
+            pub fn truncating(iter: I) -> Self
+            where
+                I: ExactSizeIterator,
+            {
+                Self {
+                    len: iter.len(),
+                    mode: Mode::Truncate,
+                    iter,
+                }
+            }
+
+            /// Initializes from an iterator whose length is known exactly, without checking it.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `iter` has more than `usize::MAX` items.
+            pub fn new_trusted(iter: I) -> Self
+            where
+                I: TrustedLen,
+            {
+                let len = iter.size_hint().1.expect("iterator length overflow");
+                Self::with_len(iter, len)
+            }
+
+            /// Initializes `len` items from `iter`, without checking whether it has any more.
+            ///
+            /// Fails with [`InitFromIterError::TooShort`] if `iter` yields fewer than `len` items.
+            pub fn with_len(iter: I, len: usize) -> Self {
+                Self {
+                    len,
+                    mode: Mode::Trusted,
+                    iter,
+                }
+            }
+
+            /// Panic instead of failing if the iterator yields the wrong number of items.
+            ///
+            /// The resulting initializer only fails if an item does, so infallible items give an
+            /// infallible initializer.
+            pub fn unwrap_len(self) -> UnwrapLen<Self> {
+                UnwrapLen { init: self }
+            }
+        }
+    };
+}
+
+/// Initialize a slice from the items of an iterator.
+#[derive(Clone)]
+pub struct FromIter<I> {
+    iter: I,
+    len: usize,
+    mode: Mode,
+}
+
+from_iter_constructors!(FromIter);
+
+unsafe impl<T, I: Iterator<Item = T>, Extra> PinInit<[T], InitFromIterError, Extra>
+    for FromIter<I>
+{
+    fn metadata(&self) -> usize {
+        self.len
+    }
+
+    unsafe fn init(self, dst: *mut [T], _: Extra) -> Result<(), InitFromIterError> {
+        debug_assert_eq!(dst.len(), self.len);
+        // SAFETY: discharged to caller
+        unsafe { fill(dst, self.mode, self.iter.map(Ok)) }
+    }
+}
+unsafe impl<T, I: Iterator<Item = T>, Extra> Init<[T], InitFromIterError, Extra> for FromIter<I> {}
+
+/// Initialize a slice from the items of an iterator of `Result`s, failing at the first `Err`.
+///
+/// ```rust
+/// # use in_place_init::InitFromIterError;
+/// #[derive(Debug, PartialEq)]
+/// enum Error {
+///     Parse,
+///     Length(InitFromIterError),
+/// }
+/// impl From<InitFromIterError> for Error {
+///     fn from(err: InitFromIterError) -> Self {
+///         Error::Length(err)
+///     }
+/// }
+///
+/// let parse = |s: &str| s.parse::<u8>().map_err(|_| Error::Parse);
+/// let bx: Result<Box<[u8]>, Error> =
+///     in_place_init::try_new_boxed(in_place_init::from_try_iter(["1", "2"].map(parse)));
+/// assert_eq!(bx.unwrap()[..], [1, 2]);
+/// let bx: Result<Box<[u8]>, Error> =
+///     in_place_init::try_new_boxed(in_place_init::from_try_iter(["1", "x"].map(parse)));
+/// assert_eq!(bx, Err(Error::Parse));
+/// ```
+#[derive(Clone)]
+pub struct FromTryIter<I> {
+    iter: I,
+    len: usize,
+    mode: Mode,
+}
+
+from_iter_constructors!(FromTryIter);
+
+unsafe impl<T, Error: From<InitFromIterError>, I: Iterator<Item = Result<T, Error>>, Extra>
+    PinInit<[T], Error, Extra> for FromTryIter<I>
+{
+    fn metadata(&self) -> usize {
+        self.len
+    }
+
+    unsafe fn init(self, dst: *mut [T], _: Extra) -> Result<(), Error> {
+        debug_assert_eq!(dst.len(), self.len);
+        // SAFETY: discharged to caller
+        unsafe { fill(dst, self.mode, self.iter) }
+    }
+}
+unsafe impl<T, Error: From<InitFromIterError>, I: Iterator<Item = Result<T, Error>>, Extra>
+    Init<[T], Error, Extra> for FromTryIter<I>
+{
+}
+
+/// Initialize a slice from an iterator of initializers.
+#[derive(Clone)]
+pub struct FromInitIter<I> {
+    iter: I,
+    len: usize,
+    mode: Mode,
+}
+
+from_iter_constructors!(FromInitIter);
+
+unsafe impl<T, Error: From<InitFromIterError>, J: PinInit<T, Error>, I: Iterator<Item = J>, Extra>
+    PinInit<[T], Error, Extra> for FromInitIter<I>
+{
+    fn metadata(&self) -> usize {
+        self.len
+    }
+
+    unsafe fn init(self, dst: *mut [T], _: Extra) -> Result<(), Error> {
+        debug_assert_eq!(dst.len(), self.len);
+        // SAFETY: discharged to caller
+        unsafe { fill(dst, self.mode, self.iter) }
+    }
+}
+unsafe impl<T, Error: From<InitFromIterError>, J: Init<T, Error>, I: Iterator<Item = J>, Extra>
+    Init<[T], Error, Extra> for FromInitIter<I>
+{
+}
+
+/// Initialize a slice from an iterator, panicking if it yields the wrong number of items.
+///
+/// Created by `unwrap_len` on [`FromIter`], [`FromTryIter`] and [`FromInitIter`].
+///
+/// ```rust
+/// let inits = [1, 2].map(|x| in_place_init::with(move |()| x * 10));
+/// let bx: Box<[u32]> = in_place_init::new_boxed(in_place_init::from_init_iter(inits).unwrap_len());
+/// assert_eq!(*bx, [10, 20]);
+/// ```
+#[derive(Clone)]
+pub struct UnwrapLen<I> {
+    init: I,
+}
+
+unsafe impl<T, I: Iterator<Item = T>, Extra> PinInit<[T], !, Extra> for UnwrapLen<FromIter<I>> {
+    fn metadata(&self) -> usize {
+        self.init.len
+    }
+
+    #[track_caller]
+    unsafe fn init(self, dst: *mut [T], extra: Extra) -> Result<(), !> {
+        // SAFETY: discharged to caller
+        let result = unsafe { self.init.init(dst, extra) };
+        result.map_err(|err| panic!("{err}"))
+    }
+}
+unsafe impl<T, I: Iterator<Item = T>, Extra> Init<[T], !, Extra> for UnwrapLen<FromIter<I>> {}
+
+unsafe impl<T, Error, I: Iterator<Item = Result<T, Error>>, Extra> PinInit<[T], Error, Extra>
+    for UnwrapLen<FromTryIter<I>>
+{
+    fn metadata(&self) -> usize {
+        self.init.len
+    }
+
+    #[track_caller]
+    unsafe fn init(self, dst: *mut [T], _: Extra) -> Result<(), Error> {
+        debug_assert_eq!(dst.len(), self.init.len);
+        let iter = self.init.iter.map(|item| item.map_err(FillError::Item));
+        // SAFETY: discharged to caller
+        unsafe { fill(dst, self.init.mode, iter) }.map_err(FillError::unwrap_len)
+    }
+}
+unsafe impl<T, Error, I: Iterator<Item = Result<T, Error>>, Extra> Init<[T], Error, Extra>
+    for UnwrapLen<FromTryIter<I>>
+{
+}
+
+unsafe impl<T, Error, J: PinInit<T, Error>, I: Iterator<Item = J>, Extra> PinInit<[T], Error, Extra>
+    for UnwrapLen<FromInitIter<I>>
+{
+    fn metadata(&self) -> usize {
+        self.init.len
+    }
+
+    #[track_caller]
+    unsafe fn init(self, dst: *mut [T], _: Extra) -> Result<(), Error> {
+        debug_assert_eq!(dst.len(), self.init.len);
+        let iter = self
+            .init
+            .iter
+            .map(|init| crate::map_err(FillError::Item, init));
+        // SAFETY: discharged to caller
+        unsafe { fill(dst, self.init.mode, iter) }.map_err(FillError::unwrap_len)
+    }
+}
+unsafe impl<T, Error, J: Init<T, Error>, I: Iterator<Item = J>, Extra> Init<[T], Error, Extra>
+    for UnwrapLen<FromInitIter<I>>
+{
+}
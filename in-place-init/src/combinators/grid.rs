@@ -0,0 +1,140 @@
+use crate::{Init, PinInit, VecExt};
+
+/// The order in which the elements of a [`Grid`] are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GridOrder {
+    /// The last index varies fastest, e.g. each row of a 2-D grid is contiguous.
+    #[default]
+    RowMajor,
+    /// The first index varies fastest, e.g. each column of a 2-D grid is contiguous.
+    ColumnMajor,
+}
+
+/// Initialize a slice as a `D`-dimensional grid with a runtime shape, by creating an
+/// initializer for each element from its index.
+///
+/// ```rust
+/// let bx: Box<[(usize, usize)]> =
+///     in_place_init::new_boxed(in_place_init::grid_for_each(2, 3, |r, c| (r, c)));
+/// assert_eq!(*bx, [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+///
+/// let bx: Box<[(usize, usize)]> = in_place_init::new_boxed(
+///     in_place_init::grid_for_each(2, 3, |r, c| (r, c)).column_major(),
+/// );
+/// assert_eq!(*bx, [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2), (1, 2)]);
+///
+/// let bx: Box<[usize]> =
+///     in_place_init::new_boxed(in_place_init::grid_for_each_nd([2, 2, 2], |[x, y, z]| {
+///         x * 100 + y * 10 + z
+///     }));
+/// assert_eq!(*bx, [0, 1, 10, 11, 100, 101, 110, 111]);
+/// ```
+#[derive(Clone)]
+pub struct Grid<F, const D: usize> {
+    shape: [usize; D],
+    order: GridOrder,
+    func: F,
+}
+
+impl<F, const D: usize> Grid<F, D> {
+    pub fn new(shape: [usize; D], func: F) -> Self {
+        Self {
+            shape,
+            order: GridOrder::RowMajor,
+            func,
+        }
+    }
+
+    pub fn with_order(self, order: GridOrder) -> Self {
+        Self { order, ..self }
+    }
+
+    pub fn row_major(self) -> Self {
+        self.with_order(GridOrder::RowMajor)
+    }
+
+    pub fn column_major(self) -> Self {
+        self.with_order(GridOrder::ColumnMajor)
+    }
+
+    pub fn shape(&self) -> [usize; D] {
+        self.shape
+    }
+
+    /// The number of elements in the grid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it overflows `usize`.
+    pub fn len(&self) -> usize {
+        self.shape
+            .iter()
+            .try_fold(1usize, |len, &dim| len.checked_mul(dim))
+            .expect("slice length overflow")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shape.contains(&0)
+    }
+}
+
+/// Advance `idx` to the next index in `order`. Returns `false` after the last index.
+fn next_index<const D: usize>(idx: &mut [usize; D], shape: &[usize; D], order: GridOrder) -> bool {
+    let mut advance = |axis: usize| {
+        idx[axis] += 1;
+        if idx[axis] < shape[axis] {
+            return true;
+        }
+        idx[axis] = 0;
+        false
+    };
+    match order {
+        GridOrder::RowMajor => (0..D).rev().any(&mut advance),
+        GridOrder::ColumnMajor => (0..D).any(&mut advance),
+    }
+}
+
+unsafe impl<
+    T,
+    const D: usize,
+    Error,
+    Extra: Clone,
+    I: PinInit<T, Error, Extra>,
+    F: FnMut([usize; D]) -> I,
+> PinInit<[T], Error, Extra> for Grid<F, D>
+{
+    fn metadata(&self) -> usize {
+        self.len()
+    }
+
+    unsafe fn init(mut self, dst: *mut [T], extra: Extra) -> Result<(), Error> {
+        let mut buf = unsafe { noop_allocator::owning_slice::empty_from_raw(dst) };
+        debug_assert_eq!(buf.capacity(), self.len());
+        if self.is_empty() {
+            return Ok(());
+        }
+        let mut idx = [0; D];
+        loop {
+            let init = crate::with_extra((self.func)(idx), extra.clone());
+            // SAFETY: either `init: Init`, or we treat the destination as pinned
+            let init = unsafe { crate::assert_pinned(init) };
+            // SAFETY: there is excess capacity, since there is one element per index
+            unsafe { buf.try_push_emplace_within_capacity_unchecked(init) }?;
+            if !next_index(&mut idx, &self.shape, self.order) {
+                break;
+            }
+        }
+        core::mem::forget(buf);
+        Ok(())
+    }
+}
+unsafe impl<
+    T,
+    const D: usize,
+    Error,
+    Extra: Clone,
+    I: Init<T, Error, Extra>,
+    F: FnMut([usize; D]) -> I,
+> Init<[T], Error, Extra> for Grid<F, D>
+{
+}
@@ -2,18 +2,24 @@ pub(crate) mod try_with;
 pub(crate) mod with;
 
 pub(crate) mod for_each;
+pub(crate) mod for_each_chunk;
 pub(crate) mod for_each_with;
+pub(crate) mod from_iter;
+pub(crate) mod grid;
 pub(crate) mod repeat;
 
 pub(crate) mod fail;
 
 pub(crate) mod map_err;
+pub(crate) mod or_else;
 
 pub(crate) mod ignore_extra;
 pub(crate) mod map_extra;
 pub(crate) mod with_extra;
 
 pub(crate) mod chain;
+pub(crate) mod concat;
+pub(crate) mod format_str;
 
 pub(crate) mod uninit;
 pub(crate) mod zeroed;
@@ -31,4 +37,6 @@ pub(crate) mod as_utf8;
 
 pub(crate) mod flatten;
 
+pub(crate) mod either;
+
 pub(crate) mod unsize;
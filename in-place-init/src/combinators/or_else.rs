@@ -0,0 +1,225 @@
+use core::{
+    fmt,
+    marker::{MetaSized, PhantomData},
+    ptr::Pointee,
+};
+
+use crate::{Init, PinInit};
+
+/// The error when an initializer has different metadata (e.g. a different slice length) than
+/// the destination that was already allocated for it.
+///
+/// This is returned by [`OrElse`] and [`Or`] for a fallback of a different length, and by
+/// [`ForEachChunk`](crate::ForEachChunk) for a mismatched chunk. Infallible initializers
+/// (with `Error = !`) panic with this error instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataMismatch;
+
+impl fmt::Display for MetadataMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("initializer metadata does not match the destination")
+    }
+}
+
+impl core::error::Error for MetadataMismatch {}
+
+/// Initialize `dst` with `fallback` after the first initializer failed.
+///
+/// # Safety
+///
+/// See [`PinInit::init`].
+unsafe fn init_sized_fallback<T, Error, Extra>(
+    fallback: impl PinInit<T, Error, Extra>,
+    dst: *mut T,
+    extra: Extra,
+) -> Result<(), Error> {
+    // SAFETY: discharged to caller
+    unsafe { fallback.init(dst, extra) }
+}
+
+/// Initialize `dst` with `fallback` after the first initializer failed, if their metadata match.
+///
+/// # Safety
+///
+/// See [`PinInit::init`].
+unsafe fn init_fallback<T: MetaSized, Error: From<MetadataMismatch>, Extra>(
+    fallback: impl PinInit<T, Error, Extra>,
+    dst: *mut T,
+    extra: Extra,
+) -> Result<(), Error> {
+    if fallback.metadata() != core::ptr::metadata(dst) {
+        return Err(MetadataMismatch.into());
+    }
+    // SAFETY: discharged to caller, and the metadata matches
+    unsafe { fallback.init(dst, extra) }
+}
+
+/// Initialize `dst` with the infallible `fallback` after the first initializer failed.
+///
+/// # Panics
+///
+/// Panics with [`MetadataMismatch`] if the metadata of `fallback` and `dst` differ.
+///
+/// # Safety
+///
+/// See [`PinInit::init`].
+unsafe fn init_fallback_or_panic<T: MetaSized, Extra>(
+    fallback: impl PinInit<T, !, Extra>,
+    dst: *mut T,
+    extra: Extra,
+) -> Result<(), !> {
+    if fallback.metadata() != core::ptr::metadata(dst) {
+        panic!("{MetadataMismatch}");
+    }
+    // SAFETY: discharged to caller, and the metadata matches
+    unsafe { fallback.init(dst, extra) }
+}
+
+/// Initialize with the initializer returned by a function if the first one fails.
+pub struct OrElse<T: MetaSized, E1, I, F> {
+    result: PhantomData<fn() -> T>,
+    error: PhantomData<fn(E1)>,
+    init: I,
+    func: F,
+}
+
+impl<T: MetaSized, E1, I: Clone, F: Clone> Clone for OrElse<T, E1, I, F> {
+    fn clone(&self) -> Self {
+        Self {
+            result: PhantomData,
+            error: PhantomData,
+            init: self.init.clone(),
+            func: self.func.clone(),
+        }
+    }
+}
+
+impl<T: MetaSized, E1, I, F> OrElse<T, E1, I, F> {
+    pub fn new(init: I, func: F) -> Self {
+        Self {
+            result: PhantomData,
+            error: PhantomData,
+            init,
+            func,
+        }
+    }
+}
+
+/// Initialize with a fallback initializer if the first one fails.
+pub struct Or<T: MetaSized, E1, I, I2> {
+    result: PhantomData<fn() -> T>,
+    error: PhantomData<fn(E1)>,
+    init: I,
+    fallback: I2,
+}
+
+impl<T: MetaSized, E1, I: Clone, I2: Clone> Clone for Or<T, E1, I, I2> {
+    fn clone(&self) -> Self {
+        Self {
+            result: PhantomData,
+            error: PhantomData,
+            init: self.init.clone(),
+            fallback: self.fallback.clone(),
+        }
+    }
+}
+
+impl<T: MetaSized, E1, I, I2> Or<T, E1, I, I2> {
+    pub fn new(init: I, fallback: I2) -> Self {
+        Self {
+            result: PhantomData,
+            error: PhantomData,
+            init,
+            fallback,
+        }
+    }
+}
+
+// Sized destinations have no metadata that could differ, so only slice and `str` destinations
+// need to check the fallback. A mismatch is returned as an error if `E2: From<MetadataMismatch>`,
+// and panicked with if the fallback is infallible.
+macro_rules! fallback_impls {
+    ([$($gen:ident),*] $dst:ty, [$($e2gen:ident)?] $e2:ty, [$($bound:tt)*], $init_fallback:ident) => {
+        unsafe impl<
+            $($gen,)*
+            Extra: Clone,
+            E1,
+            $($e2gen,)?
+            I: PinInit<$dst, E1, Extra>,
+            I2: PinInit<$dst, $e2, Extra>,
+            F: FnOnce(E1) -> I2,
+        > PinInit<$dst, $e2, Extra> for OrElse<$dst, E1, I, F>
+        where
+            $($bound)*
+        {
+            fn metadata(&self) -> <$dst as Pointee>::Metadata {
+                self.init.metadata()
+            }
+
+            unsafe fn init(self, dst: *mut $dst, extra: Extra) -> Result<(), $e2> {
+                // SAFETY: discharged to caller
+                // If the first initializer fails, `dst` is uninitialized again.
+                match unsafe { self.init.init(dst, extra.clone()) } {
+                    Ok(()) => Ok(()),
+                    Err(err) => unsafe { $init_fallback((self.func)(err), dst, extra) },
+                }
+            }
+        }
+        unsafe impl<
+            $($gen,)*
+            Extra: Clone,
+            E1,
+            $($e2gen,)?
+            I: Init<$dst, E1, Extra>,
+            I2: Init<$dst, $e2, Extra>,
+            F: FnOnce(E1) -> I2,
+        > Init<$dst, $e2, Extra> for OrElse<$dst, E1, I, F>
+        where
+            $($bound)*
+        {
+        }
+
+        unsafe impl<
+            $($gen,)*
+            Extra: Clone,
+            E1,
+            $($e2gen,)?
+            I: PinInit<$dst, E1, Extra>,
+            I2: PinInit<$dst, $e2, Extra>,
+        > PinInit<$dst, $e2, Extra> for Or<$dst, E1, I, I2>
+        where
+            $($bound)*
+        {
+            fn metadata(&self) -> <$dst as Pointee>::Metadata {
+                self.init.metadata()
+            }
+
+            unsafe fn init(self, dst: *mut $dst, extra: Extra) -> Result<(), $e2> {
+                // SAFETY: discharged to caller
+                // If the first initializer fails, `dst` is uninitialized again.
+                match unsafe { self.init.init(dst, extra.clone()) } {
+                    Ok(()) => Ok(()),
+                    Err(_) => unsafe { $init_fallback(self.fallback, dst, extra) },
+                }
+            }
+        }
+        unsafe impl<
+            $($gen,)*
+            Extra: Clone,
+            E1,
+            $($e2gen,)?
+            I: Init<$dst, E1, Extra>,
+            I2: Init<$dst, $e2, Extra>,
+        > Init<$dst, $e2, Extra> for Or<$dst, E1, I, I2>
+        where
+            $($bound)*
+        {
+        }
+    };
+}
+
+fallback_impls!([T] T, [E2] E2, [], init_sized_fallback);
+fallback_impls!([T] [T], [E2] E2, [E2: From<MetadataMismatch>], init_fallback);
+fallback_impls!([T] [T], [] !, [], init_fallback_or_panic);
+fallback_impls!([] str, [E2] E2, [E2: From<MetadataMismatch>], init_fallback);
+fallback_impls!([] str, [] !, [], init_fallback_or_panic);
@@ -0,0 +1,145 @@
+use alloc::boxed::Box;
+use core::{marker::MetaSized, ptr::Pointee};
+
+use crate::{Init, PinInit};
+
+/// A dyn-compatible version of [`PinInit`], for type-erased initializers.
+///
+/// This is implemented for every [`PinInit`]. Use [`BoxedPinInit`] to turn a
+/// `Box<dyn DynPinInit<..>>` back into a [`PinInit`].
+///
+/// # Safety
+///
+/// See [`PinInit`].
+pub unsafe trait DynPinInit<Dst: MetaSized, Error = !, Extra = ()> {
+    /// See [`PinInit::metadata`].
+    fn dyn_metadata(&self) -> <Dst as Pointee>::Metadata;
+
+    /// See [`PinInit::init`].
+    ///
+    /// # Safety
+    ///
+    /// See [`PinInit::init`].
+    unsafe fn dyn_init(self: Box<Self>, dst: *mut Dst, extra: Extra) -> Result<(), Error>;
+}
+
+unsafe impl<Dst: MetaSized, Error, Extra, I: PinInit<Dst, Error, Extra>>
+    DynPinInit<Dst, Error, Extra> for I
+{
+    fn dyn_metadata(&self) -> <Dst as Pointee>::Metadata {
+        self.metadata()
+    }
+
+    unsafe fn dyn_init(self: Box<Self>, dst: *mut Dst, extra: Extra) -> Result<(), Error> {
+        // SAFETY: discharged to caller
+        unsafe { (*self).init(dst, extra) }
+    }
+}
+
+/// A dyn-compatible version of [`Init`], for type-erased initializers.
+///
+/// This is implemented for every [`Init`]. Use [`BoxedInit`] to turn a `Box<dyn DynInit<..>>`
+/// back into an [`Init`].
+///
+/// # Safety
+///
+/// See [`Init`].
+pub unsafe trait DynInit<Dst: MetaSized, Error = !, Extra = ()>:
+    DynPinInit<Dst, Error, Extra>
+{
+}
+
+unsafe impl<Dst: MetaSized, Error, Extra, I: Init<Dst, Error, Extra>> DynInit<Dst, Error, Extra>
+    for I
+{
+}
+
+/// A boxed, type-erased [`PinInit`].
+pub struct BoxedPinInit<'a, Dst: MetaSized, Error = !, Extra = ()> {
+    init: Box<dyn DynPinInit<Dst, Error, Extra> + 'a>,
+}
+
+impl<'a, Dst: MetaSized, Error, Extra> BoxedPinInit<'a, Dst, Error, Extra> {
+    pub fn new(init: impl PinInit<Dst, Error, Extra> + 'a) -> Self {
+        Self {
+            init: Box::new(init),
+        }
+    }
+
+    pub fn from_box(init: Box<dyn DynPinInit<Dst, Error, Extra> + 'a>) -> Self {
+        Self { init }
+    }
+
+    pub fn into_box(self) -> Box<dyn DynPinInit<Dst, Error, Extra> + 'a> {
+        self.init
+    }
+}
+
+unsafe impl<Dst: MetaSized, Error, Extra> PinInit<Dst, Error, Extra>
+    for BoxedPinInit<'_, Dst, Error, Extra>
+{
+    fn metadata(&self) -> <Dst as Pointee>::Metadata {
+        (*self.init).dyn_metadata()
+    }
+
+    unsafe fn init(self, dst: *mut Dst, extra: Extra) -> Result<(), Error> {
+        // SAFETY: discharged to caller
+        unsafe { self.init.dyn_init(dst, extra) }
+    }
+}
+
+/// A boxed, type-erased [`Init`].
+///
+/// ```rust
+/// # use in_place_init::{BoxedInit, VecExt};
+/// let inits: Vec<BoxedInit<'_, String>> = vec![
+///     BoxedInit::new(String::from("hello")),
+///     BoxedInit::new(in_place_init::with(|()| "world".to_owned())),
+/// ];
+/// let mut v: Vec<String> = Vec::new();
+/// v.extend_emplace(inits);
+/// assert_eq!(v, ["hello", "world"]);
+///
+/// let bx: Box<[u8]> = in_place_init::new_boxed(BoxedInit::new(in_place_init::slice_repeat(3, 7)));
+/// assert_eq!(*bx, [7, 7, 7]);
+/// ```
+pub struct BoxedInit<'a, Dst: MetaSized, Error = !, Extra = ()> {
+    init: Box<dyn DynInit<Dst, Error, Extra> + 'a>,
+}
+
+impl<'a, Dst: MetaSized, Error, Extra> BoxedInit<'a, Dst, Error, Extra> {
+    pub fn new(init: impl Init<Dst, Error, Extra> + 'a) -> Self {
+        Self {
+            init: Box::new(init),
+        }
+    }
+
+    pub fn from_box(init: Box<dyn DynInit<Dst, Error, Extra> + 'a>) -> Self {
+        Self { init }
+    }
+
+    pub fn into_box(self) -> Box<dyn DynInit<Dst, Error, Extra> + 'a> {
+        self.init
+    }
+
+    pub fn into_pinned(self) -> BoxedPinInit<'a, Dst, Error, Extra> {
+        BoxedPinInit::from_box(self.init)
+    }
+}
+
+unsafe impl<Dst: MetaSized, Error, Extra> PinInit<Dst, Error, Extra>
+    for BoxedInit<'_, Dst, Error, Extra>
+{
+    fn metadata(&self) -> <Dst as Pointee>::Metadata {
+        (*self.init).dyn_metadata()
+    }
+
+    unsafe fn init(self, dst: *mut Dst, extra: Extra) -> Result<(), Error> {
+        // SAFETY: discharged to caller
+        unsafe { self.init.dyn_init(dst, extra) }
+    }
+}
+unsafe impl<Dst: MetaSized, Error, Extra> Init<Dst, Error, Extra>
+    for BoxedInit<'_, Dst, Error, Extra>
+{
+}
@@ -0,0 +1,319 @@
+use core::{
+    marker::{MetaSized, PhantomData},
+    mem::ManuallyDrop,
+    ptr::Pointee,
+};
+
+use crate::{Init, PinInit};
+
+/// The type of the value initialized by [`init!`] or [`pin_init!`], and of its error.
+///
+/// The macros create one of these from the path they are given, so any `_` generic arguments
+/// in it are inferred once for all of the helpers below.
+#[doc(hidden)]
+pub type Marker<T, E> = PhantomData<fn(*mut T) -> E>;
+
+/// The initializer created by [`init!`] and [`pin_init!`], which runs a closure that initializes
+/// each field in place. It is [`Init`] if `PINNED` is `false`.
+#[doc(hidden)]
+pub struct InitFn<T: MetaSized, E, F, const PINNED: bool> {
+    meta: <T as Pointee>::Metadata,
+    func: F,
+    marker: Marker<T, E>,
+}
+
+impl<T: MetaSized, E, F: FnOnce(*mut T) -> Result<(), E>, const PINNED: bool>
+    InitFn<T, E, F, PINNED>
+{
+    /// # Safety
+    ///
+    /// `func(dst)` must fully initialize `dst` (which has metadata `meta`) if it returns `Ok`, and
+    /// leave it uninitialized (having dropped anything it initialized) if it returns `Err`.
+    ///
+    /// If `PINNED` is `false`, the value it initializes must not rely on not being moved.
+    pub unsafe fn new(marker: Marker<T, E>, meta: <T as Pointee>::Metadata, func: F) -> Self {
+        Self { meta, func, marker }
+    }
+}
+
+unsafe impl<T: MetaSized, E, F: FnOnce(*mut T) -> Result<(), E>, const PINNED: bool> PinInit<T, E>
+    for InitFn<T, E, F, PINNED>
+{
+    fn metadata(&self) -> <T as Pointee>::Metadata {
+        self.meta
+    }
+
+    unsafe fn init(self, dst: *mut T, _: ()) -> Result<(), E> {
+        (self.func)(dst)
+    }
+}
+unsafe impl<T: MetaSized, E, F: FnOnce(*mut T) -> Result<(), E>> Init<T, E>
+    for InitFn<T, E, F, false>
+{
+}
+
+/// A field given by value to [`init!`] or [`pin_init!`], which can be written with any error type.
+#[doc(hidden)]
+pub struct Value<T>(pub T);
+
+unsafe impl<T, E> PinInit<T, E> for Value<T> {
+    fn metadata(&self) {}
+
+    unsafe fn init(self, dst: *mut T, _: ()) -> Result<(), E> {
+        unsafe { dst.write(self.0) };
+        Ok(())
+    }
+}
+unsafe impl<T, E> Init<T, E> for Value<T> {}
+
+/// Type-check `check`, which matches the fields given to [`init!`] or [`pin_init!`], without
+/// running it.
+#[doc(hidden)]
+pub fn check_fields<T: MetaSized, E>(_: Marker<T, E>, check: fn(&T)) {
+    _ = check;
+}
+
+/// The metadata of the value initialized by `init`, for the field of `T` that `field` projects to.
+#[doc(hidden)]
+pub fn tail_metadata<T: MetaSized, F: MetaSized, E, I: PinInit<F, E>>(
+    _: Marker<T, E>,
+    _field: fn(*mut T) -> *mut F,
+    init: &I,
+) -> <F as Pointee>::Metadata {
+    init.metadata()
+}
+
+/// Initialize a field of a value initialized by [`init!`].
+///
+/// # Safety
+///
+/// See [`PinInit::init`].
+#[doc(hidden)]
+pub unsafe fn init_field<F: MetaSized, E, I: Init<F, E>>(init: I, dst: *mut F) -> Result<(), E> {
+    unsafe { init.init(dst, ()) }
+}
+
+/// Initialize a field of a value initialized by [`pin_init!`].
+///
+/// # Safety
+///
+/// See [`PinInit::init`].
+#[doc(hidden)]
+pub unsafe fn pin_init_field<F: MetaSized, E, I: PinInit<F, E>>(
+    init: I,
+    dst: *mut F,
+) -> Result<(), E> {
+    unsafe { init.init(dst, ()) }
+}
+
+/// A field of a default value being replaced by [`init!`] or [`pin_init!`] with `..Default::default()`.
+///
+/// If the field's initializer fails or panics, dropping this puts the default back into the field
+/// and drops the whole value.
+#[doc(hidden)]
+pub struct RestoreDefault<T, F> {
+    dst: *mut T,
+    field: *mut F,
+    default: ManuallyDrop<F>,
+}
+
+impl<T, F> RestoreDefault<T, F> {
+    /// Move the default value out of `field`, leaving it uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// `dst` must point to an initialized `T`, whose field `field` points to. If this is dropped
+    /// (rather than [`finish`](Self::finish)ed), `*dst` must not be used afterwards.
+    pub unsafe fn new(dst: *mut T, field: *mut F) -> Self {
+        // SAFETY: discharged to caller
+        let default = unsafe { field.read() };
+        Self {
+            dst,
+            field,
+            default: ManuallyDrop::new(default),
+        }
+    }
+
+    /// Drop the default value, now that the field has been initialized again.
+    pub fn finish(self) {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is not dropped, so the default value is only taken once
+        unsafe { ManuallyDrop::drop(&mut this.default) };
+    }
+}
+
+impl<T, F> Drop for RestoreDefault<T, F> {
+    fn drop(&mut self) {
+        // SAFETY: the field is uninitialized after its initializer failed or panicked, so putting
+        // the default back makes `*dst` initialized again, and the caller does not use it after
+        unsafe {
+            self.field.write(ManuallyDrop::take(&mut self.default));
+            core::ptr::drop_in_place(self.dst);
+        }
+    }
+}
+
+/// Create an initializer for a struct from an initializer or value for each field, like a struct
+/// literal.
+///
+/// ```rust
+/// # use in_place_init::init;
+/// struct Foo {
+///     id: u32,
+///     buf: [u8; 1024],
+/// }
+///
+/// let foo: Box<Foo> = in_place_init::new_boxed(init!(Foo {
+///     id: 1,
+///     buf <- in_place_init::array_repeat(0),
+/// }));
+/// assert_eq!(foo.buf, [0; 1024]);
+/// ```
+///
+/// Each field is given either by value, with `field: value`, or by an initializer, with
+/// `field <- init`. Every field must be given exactly once, unless the list ends with `..Default`
+/// (or `..Default::default()`), in which case the struct is first initialized with
+/// `Default::default()`, and the given fields replace the default ones.
+///
+/// The values and initializers are evaluated when the macro is, in order, and the fields are
+/// initialized in the same order. If an initializer fails, its error is returned by the
+/// initializer created by this macro, and the fields initialized so far are dropped.
+///
+/// The initializers must implement [`Init`], so the initializer created by this macro does too.
+/// Use [`pin_init!`] to allow [`PinInit`] initializers.
+///
+/// The struct may have an unsized tail field, if it is the last field given.
+#[macro_export]
+macro_rules! init {
+    ($t:path { $($fields:tt)* }) => {
+        $crate::__init!(@parse [init_field false] $t; []; $($fields)*)
+    };
+}
+
+/// Create a pinned initializer for a struct from an initializer or value for each field, like a
+/// struct literal.
+///
+/// Like [`init!`], but the field initializers only need to implement [`PinInit`], so the created
+/// initializer only implements [`PinInit`].
+///
+/// ```rust
+/// # use in_place_init::pin_init;
+/// # use std::marker::PhantomPinned;
+/// struct Foo {
+///     id: u32,
+///     _pinned: PhantomPinned,
+/// }
+///
+/// let foo = in_place_init::new_pinned::<Foo>(pin_init!(Foo {
+///     id: 1,
+///     _pinned: PhantomPinned,
+/// }));
+/// assert_eq!(foo.id, 1);
+/// ```
+#[macro_export]
+macro_rules! pin_init {
+    ($t:path { $($fields:tt)* }) => {
+        $crate::__init!(@parse [pin_init_field true] $t; []; $($fields)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __init {
+    (@parse $kind:tt $t:path; [$($done:tt)*]; .. Default $(:: default ())? $(,)?) => {
+        $crate::__init!(@default $kind $t; [$($done)*])
+    };
+    (@parse $kind:tt $t:path; [$($done:tt)*]; $field:tt : $value:expr $(, $($rest:tt)*)?) => {
+        $crate::__init!(@parse $kind $t; [$($done)* [$field $crate::__private::Value($value)]]; $($($rest)*)?)
+    };
+    (@parse $kind:tt $t:path; [$($done:tt)*]; $field:tt <- $init:expr $(, $($rest:tt)*)?) => {
+        $crate::__init!(@parse $kind $t; [$($done)* [$field $init]]; $($($rest)*)?)
+    };
+    (@parse $kind:tt $t:path; [$($done:tt)*];) => {
+        $crate::__init!(@exact $kind $t; [$($done)*])
+    };
+
+    // The initializers, as nested pairs `(first, (second, (..., ())))`.
+    (@nest) => { () };
+    (@nest $init:expr, $($rest:expr,)*) => { ($init, $crate::__init!(@nest $($rest,)*)) };
+
+    // The metadata of the last field's initializer, at `$inits.1.1...0`.
+    (@metadata $marker:ident; $inits:ident; [$($path:tt)*];) => { () };
+    (@metadata $marker:ident; $inits:ident; [$($path:tt)*]; $field:tt) => {
+        $crate::__private::tail_metadata(
+            $marker,
+            |dst| unsafe { &raw mut (*dst).$field },
+            &$inits $($path)* .0,
+        )
+    };
+    (@metadata $marker:ident; $inits:ident; [$($path:tt)*]; $field:tt $($rest:tt)+) => {
+        $crate::__init!(@metadata $marker; $inits; [$($path)* .1]; $($rest)+)
+    };
+
+    (@exact [$init_field:ident $pinned:literal] $t:path; [$([$field:tt $init:expr])*]) => {{
+        let marker: $crate::__private::Marker<$t, _> = ::core::marker::PhantomData;
+        // Check that every field is given exactly once.
+        $crate::__private::check_fields(marker, |value| match *value {
+            $t { $($field: _,)* } => {}
+        });
+        let inits = $crate::__init!(@nest $($init,)*);
+        let meta = $crate::__init!(@metadata marker; inits; []; $($field)*);
+        // SAFETY: every field is initialized (since they are all listed), and if one fails, the
+        // fields before it are dropped by their guards. Field initializers can only rely on not
+        // being moved for `pin_init!`, which is the only one that passes `PINNED = true`.
+        unsafe {
+            $crate::__private::InitFn::<_, _, _, $pinned>::new(marker, meta, move |dst| {
+                let rest = inits;
+                let guards = ();
+                $(
+                    let (init, rest) = rest;
+                    // The error path is unreachable for infallible initializers.
+                    #[allow(unreachable_code)]
+                    let guards = {
+                        let dst = &raw mut (*dst).$field;
+                        if let ::core::result::Result::Err(err) = $crate::__private::$init_field(init, dst) {
+                            return ::core::result::Result::Err(err);
+                        }
+                        ($crate::noop_allocator::owning_ref::from_raw(dst), guards)
+                    };
+                )*
+                let () = rest;
+                ::core::mem::forget(guards);
+                ::core::result::Result::Ok(())
+            })
+        }
+    }};
+
+    (@default [$init_field:ident $pinned:literal] $t:path; [$([$field:tt $init:expr])*]) => {{
+        let marker: $crate::__private::Marker<$t, _> = ::core::marker::PhantomData;
+        // Check that every field is given at most once.
+        $crate::__private::check_fields(marker, |value| match *value {
+            $t { $($field: _,)* .. } => {}
+        });
+        let inits = $crate::__init!(@nest $($init,)*);
+        // SAFETY: `dst` is initialized with a default value, and each given field is replaced
+        // in place. If one fails or panics, its default value is put back by its guard, and the
+        // whole value is dropped.
+        // Field initializers can only rely on not being moved for `pin_init!`.
+        unsafe {
+            $crate::__private::InitFn::<_, _, _, $pinned>::new(marker, (), move |dst| {
+                dst.write(::core::default::Default::default());
+                let rest = inits;
+                $(
+                    let (init, rest) = rest;
+                    let field = &raw mut (*dst).$field;
+                    // If the initializer fails or panics, this puts the default value back and
+                    // drops the whole value.
+                    let guard = $crate::__private::RestoreDefault::new(dst, field);
+                    #[allow(unreachable_code)]
+                    if let ::core::result::Result::Err(err) = $crate::__private::$init_field(init, field) {
+                        return ::core::result::Result::Err(err);
+                    }
+                    guard.finish();
+                )*
+                let () = rest;
+                ::core::result::Result::Ok(())
+            })
+        }
+    }};
+}
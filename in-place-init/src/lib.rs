@@ -7,6 +7,12 @@
 #![feature(clone_to_uninit)]
 #![feature(doc_auto_cfg)]
 #![feature(unsize)]
+#![feature(unique_rc_arc)]
+#![feature(slice_range)]
+#![feature(binary_heap_from_raw_vec)]
+#![feature(min_specialization)]
+#![feature(try_reserve_kind)]
+#![feature(trusted_len)]
 #![no_std]
 
 extern crate alloc;
@@ -30,8 +36,51 @@ mod allocation;
 
 mod util;
 
+mod stack;
+
+mod init_macro;
+
+mod dyn_init;
+pub use dyn_init::{BoxedInit, BoxedPinInit, DynInit, DynPinInit};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::init_macro::{
+        InitFn, Marker, RestoreDefault, Value, check_fields, init_field, pin_init_field,
+        tail_metadata,
+    };
+    pub use crate::stack::StackSlot;
+}
+
+#[cfg(target_has_atomic = "8")]
+mod static_slot;
+#[cfg(target_has_atomic = "8")]
+pub use static_slot::{AlreadyTaken, StaticSlot};
+
+#[cfg(feature = "macros")]
+pub use in_place_init_derive::{Init, PinInit};
+
+/// A destructor for types that derive [`PinInit`](derive@PinInit) with `#[init(pinned_drop)]`.
+///
+/// The generated `Drop` impl calls this with a pinned reference, so the destructor cannot move
+/// out of `#[pin]` fields.
+#[cfg(feature = "macros")]
+pub trait PinnedDrop {
+    /// Runs the destructor.
+    ///
+    /// # Safety
+    ///
+    /// This must only be called once, by the generated `Drop` impl.
+    unsafe fn drop(self: Pin<&mut Self>);
+}
+
+/// The type of a field that has not been set yet, in a builder generated by
+/// `#[derive(Init)]` with `#[init(builder)]`.
+///
+/// The builder only implements [`PinInit`] once none of its fields are `Unset`.
 #[cfg(feature = "macros")]
-pub use in_place_init_derive::Init;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Unset;
 
 /// A trait for pinned in-place initializers.
 ///
@@ -107,6 +156,21 @@ pub trait PinInitExt<Dst: MetaSized, Error = !, Extra = ()>:
         MapErr::new(func, self)
     }
 
+    /// If `self` fails, initialize with the initializer returned by `func` instead.
+    ///
+    /// For slice and `str` destinations, the fallback must have the same length as `self`;
+    /// otherwise [`MetadataMismatch`] is returned (or, for infallible fallbacks, panicked with).
+    fn init_or_else<I2, F: FnOnce(Error) -> I2>(self, func: F) -> OrElse<Dst, Error, Self, F> {
+        OrElse::new(self, func)
+    }
+
+    /// If `self` fails, initialize with `fallback` instead.
+    ///
+    /// See [`init_or_else`](PinInitExt::init_or_else).
+    fn init_or<I2>(self, fallback: I2) -> Or<Dst, Error, Self, I2> {
+        Or::new(self, fallback)
+    }
+
     fn init_map_extra<E, F: FnOnce(E) -> Result<Extra, Error>>(
         self,
         func: F,
@@ -309,6 +373,14 @@ pub fn map_err<T: MetaSized, E1, F, I>(func: F, init: I) -> MapErr<T, E1, F, I>
     MapErr::new(func, init)
 }
 
+pub use combinators::or_else::{MetadataMismatch, Or, OrElse};
+pub fn or_else<T: MetaSized, E1, I, F>(init: I, func: F) -> OrElse<T, E1, I, F> {
+    OrElse::new(init, func)
+}
+pub fn or<T: MetaSized, E1, I, I2>(init: I, fallback: I2) -> Or<T, E1, I, I2> {
+    Or::new(init, fallback)
+}
+
 pub use combinators::assert_pinned::AssertPinned;
 pub unsafe fn assert_pinned<T, Error, Extra, I: PinInit<T, Error, Extra>>(
     init: I,
@@ -317,46 +389,21 @@ pub unsafe fn assert_pinned<T, Error, Extra, I: PinInit<T, Error, Extra>>(
     unsafe { AssertPinned::new_unchecked(init) }
 }
 
-pub struct FromIter<I: ExactSizeIterator> {
-    iter: I,
-}
-
-#[derive(Debug)]
-pub enum InitFromIterError {
-    TooShort,
-}
-
-impl<I: ExactSizeIterator> FromIter<I> {
-    pub fn new(iter: I) -> Self {
-        Self { iter }
-    }
+pub use combinators::from_iter::{
+    FromInitIter, FromIter, FromTryIter, InitFromIterError, UnwrapLen,
+};
+pub fn from_iter<I: IntoIterator<IntoIter: ExactSizeIterator>>(iter: I) -> FromIter<I::IntoIter> {
+    FromIter::new(iter.into_iter())
 }
-
-unsafe impl<T, I: ExactSizeIterator<Item = T>, Extra> PinInit<[T], InitFromIterError, Extra>
-    for FromIter<I>
-{
-    fn metadata(&self) -> usize {
-        self.iter.len()
-    }
-
-    unsafe fn init(mut self, dst: *mut [T], _: Extra) -> Result<(), InitFromIterError> {
-        let mut buf = unsafe { noop_allocator::owning_slice::empty_from_raw(dst) };
-        let count = self.iter.len();
-        debug_assert_eq!(dst.len(), count);
-        while buf.len() < count {
-            let Some(item) = self.iter.next() else {
-                return Err(InitFromIterError::TooShort);
-            };
-            // SAFETY: there is excess capacity
-            unsafe { buf.push_emplace_within_capacity_unchecked(item) };
-        }
-        core::mem::forget(buf);
-        Ok(())
-    }
+pub fn from_try_iter<I: IntoIterator<IntoIter: ExactSizeIterator>>(
+    iter: I,
+) -> FromTryIter<I::IntoIter> {
+    FromTryIter::new(iter.into_iter())
 }
-unsafe impl<T, I: ExactSizeIterator<Item = T>, Extra> Init<[T], InitFromIterError, Extra>
-    for FromIter<I>
-{
+pub fn from_init_iter<I: IntoIterator<IntoIter: ExactSizeIterator>>(
+    iter: I,
+) -> FromInitIter<I::IntoIter> {
+    FromInitIter::new(iter.into_iter())
 }
 
 pub use combinators::repeat::Repeat;
@@ -375,6 +422,35 @@ pub fn slice_for_each<F>(length: usize, func: F) -> ForEach<F, RuntimeLength> {
     ForEach::new_slice(length, func)
 }
 
+pub use combinators::for_each_chunk::ForEachChunk;
+pub fn array_for_each_chunk<const N: usize, const C: usize, F>(
+    func: F,
+) -> ForEachChunk<F, ConstLength<N>, ConstLength<C>> {
+    ForEachChunk::new_array(func)
+}
+pub fn slice_for_each_chunk<F>(
+    length: usize,
+    chunk_length: usize,
+    func: F,
+) -> ForEachChunk<F, RuntimeLength, RuntimeLength> {
+    ForEachChunk::new_slice(length, chunk_length, func)
+}
+
+pub use combinators::grid::{Grid, GridOrder};
+pub fn grid_for_each<I, F: FnMut(usize, usize) -> I>(
+    rows: usize,
+    cols: usize,
+    mut func: F,
+) -> Grid<impl FnMut([usize; 2]) -> I, 2> {
+    Grid::new([rows, cols], move |[row, col]: [usize; 2]| func(row, col))
+}
+pub fn grid_for_each_nd<const D: usize, I, F: FnMut([usize; D]) -> I>(
+    shape: [usize; D],
+    func: F,
+) -> Grid<F, D> {
+    Grid::new(shape, func)
+}
+
 pub use combinators::for_each_with::ForEachWith;
 pub fn array_for_each_with<const N: usize, F>(func: F) -> ForEachWith<F, ConstLength<N>> {
     ForEachWith::new_array(func)
@@ -388,6 +464,22 @@ pub fn chain<I1, I2>(init1: I1, init2: I2) -> Chain<I1, I2> {
     Chain::new(init1, init2)
 }
 
+pub use combinators::concat::{Concat, ConcatArray, ConcatIter};
+pub fn concat<Pieces>(pieces: Pieces) -> Concat<Pieces> {
+    Concat::new(pieces)
+}
+pub fn concat_iter<I>(pieces: impl IntoIterator<Item = I>) -> ConcatIter<I> {
+    ConcatIter::new(pieces.into_iter().collect())
+}
+pub fn concat_array<A, const P: usize, Pieces>(pieces: Pieces) -> ConcatArray<A, P, Pieces> {
+    ConcatArray::new(pieces)
+}
+
+pub use combinators::format_str::FormatStr;
+pub fn format_str<D: core::fmt::Display>(value: D) -> FormatStr<D> {
+    FormatStr::new(value)
+}
+
 pub use combinators::ignore_extra::IgnoreExtra;
 pub fn ignore_extra<T: MetaSized, I>(init: I) -> IgnoreExtra<T, I> {
     IgnoreExtra::new(init)
@@ -458,6 +550,8 @@ pub fn flatten_slice<T, const N: usize, I>(init: I) -> Flatten<[[T; N]], [T], I>
     Flatten::new_slice(init)
 }
 
+pub use combinators::either::{Either, Either3, Either4, Either5, Either6, Either7, Either8};
+
 pub use combinators::unsize::Unsize;
 pub fn unsize<T: UnsizeTrait<Dst>, Dst: MetaSized, I>(init: I) -> Unsize<T, Dst, I> {
     Unsize::new(init)
@@ -467,18 +561,59 @@ pub fn unsize<T: UnsizeTrait<Dst>, Dst: MetaSized, I>(init: I) -> Unsize<T, Dst,
 
 pub use allocation::Builder;
 
+pub use allocation::arena::Arena;
+
+pub use allocation::error::AllocOrInit;
+pub use allocation::layout::layout_for_metadata;
+
+pub use allocation::boxed::BoxExt;
+pub use allocation::boxed::{
+    checked_new_boxed, checked_new_boxed_in, checked_new_pinned, checked_new_pinned_in,
+};
+pub use allocation::boxed::{init_boxed, init_boxed_slice, try_init_boxed, try_init_boxed_slice};
 pub use allocation::boxed::{new_boxed, new_pinned, try_new_boxed, try_new_pinned};
+pub use allocation::boxed::{new_boxed_from_iter, try_new_boxed_from_iter};
 pub use allocation::boxed::{new_boxed_in, new_pinned_in, try_new_boxed_in, try_new_pinned_in};
 
-pub use allocation::vec::{VecExt, new_vec, try_new_vec};
+pub use allocation::vec::{VecExt, checked_new_vec, new_vec, try_new_vec};
+
+pub use allocation::string::{StringExt, checked_new_string, new_string, try_new_string};
 
-pub use allocation::string::{StringExt, new_string, try_new_string};
+#[cfg(feature = "assume-vec-deque-layout")]
+pub use allocation::vec_deque::VecDequeExt;
+
+pub use allocation::binary_heap::BinaryHeapExt;
 
 use crate::util::{ConstLength, RuntimeLength};
+pub use allocation::rc::{RcCyclicDst, RcDst};
+pub use allocation::rc::{
+    checked_rc_new, checked_rc_new_cyclic, checked_rc_new_cyclic_pinned, checked_rc_new_pinned,
+};
+pub use allocation::rc::{init_rc, init_rc_slice, try_init_rc, try_init_rc_slice};
 pub use allocation::rc::{rc_new, rc_new_pinned, try_rc_new, try_rc_new_pinned};
 pub use allocation::rc::{
     rc_new_cyclic, rc_new_cyclic_pinned, try_rc_new_cyclic, try_rc_new_cyclic_pinned,
 };
+pub use allocation::rc::{rc_new_from_iter, try_rc_new_from_iter};
+#[cfg(feature = "assume-rc-layout")]
+pub use allocation::rc::{
+    try_unique_rc_new, try_unique_rc_new_cyclic, unique_rc_new, unique_rc_new_cyclic,
+};
+
+pub use allocation::arc::{ArcCyclicDst, ArcDst};
+pub use allocation::arc::{arc_new, arc_new_pinned, try_arc_new, try_arc_new_pinned};
+pub use allocation::arc::{
+    arc_new_cyclic, arc_new_cyclic_pinned, try_arc_new_cyclic, try_arc_new_cyclic_pinned,
+};
+pub use allocation::arc::{arc_new_from_iter, try_arc_new_from_iter};
+pub use allocation::arc::{
+    checked_arc_new, checked_arc_new_cyclic, checked_arc_new_cyclic_pinned, checked_arc_new_pinned,
+};
+pub use allocation::arc::{init_arc, init_arc_slice, try_init_arc, try_init_arc_slice};
+#[cfg(feature = "assume-rc-layout")]
+pub use allocation::arc::{
+    try_unique_arc_new, try_unique_arc_new_cyclic, unique_arc_new, unique_arc_new_cyclic,
+};
 
 /// Initialize a `MaybeUninit<T>` and return a reference to the newly initialized slot.
 ///
@@ -526,6 +661,80 @@ pub fn initialize_owned<T>(slot: &mut MaybeUninit<T>, init: impl Init<T>) -> Own
     try_initialize_owned(slot, init).unwrap_or_else(|e| match e {})
 }
 
+/// Initialize a `[MaybeUninit<T>]` and return a reference to the newly initialized slice.
+///
+/// Code that receives the mutable reference returned by this function needs to keep in mind
+/// that the destructors are not run for the elements if the slice leaves scope without
+/// a call to [`MaybeUninit::assume_init_drop`] or similar.
+///
+/// # Panics
+///
+/// Panics if `init`'s length does not match the length of `slot`.
+pub fn try_initialize_slice<T, Error>(
+    slot: &mut [MaybeUninit<T>],
+    init: impl Init<[T], Error>,
+) -> Result<&mut [T], Error> {
+    assert_eq!(
+        init.metadata(),
+        slot.len(),
+        "initializer length does not match the slice length"
+    );
+    let ptr = slot as *mut [MaybeUninit<T>] as *mut [T];
+    // SAFETY: `slot` is uniquely borrowed, so it is valid for writes, and the length matches
+    unsafe {
+        init.init(ptr, ())?;
+    }
+    // SAFETY: we just initialized `slot`
+    unsafe { Ok(&mut *ptr) }
+}
+
+/// Initialize a `[MaybeUninit<T>]` and return a reference to the newly initialized slice.
+///
+/// Code that receives the mutable reference returned by this function needs to keep in mind
+/// that the destructors are not run for the elements if the slice leaves scope without
+/// a call to [`MaybeUninit::assume_init_drop`] or similar.
+///
+/// # Panics
+///
+/// Panics if `init`'s length does not match the length of `slot`.
+pub fn initialize_slice<T>(slot: &mut [MaybeUninit<T>], init: impl Init<[T]>) -> &mut [T] {
+    try_initialize_slice(slot, init).unwrap_or_else(|e| match e {})
+}
+
+/// Initialize a `[MaybeUninit<T>]` and return a owning reference to the newly initialized slice.
+///
+/// # Panics
+///
+/// Panics if `init`'s length does not match the length of `slot`.
+pub fn try_initialize_slice_owned<T, Error>(
+    slot: &mut [MaybeUninit<T>],
+    init: impl Init<[T], Error>,
+) -> Result<OwningRef<'_, [T]>, Error> {
+    assert_eq!(
+        init.metadata(),
+        slot.len(),
+        "initializer length does not match the slice length"
+    );
+    // SAFETY: `slot` is uniquely borrowed, so it is valid for writes, and the length matches
+    unsafe {
+        init.init(slot as *mut [MaybeUninit<T>] as *mut [T], ())?;
+    }
+    // SAFETY: we just initialized `slot`
+    unsafe { Ok(noop_allocator::owning_ref::from_maybeuninit_slice(slot)) }
+}
+
+/// Initialize a `[MaybeUninit<T>]` and return a owning reference to the newly initialized slice.
+///
+/// # Panics
+///
+/// Panics if `init`'s length does not match the length of `slot`.
+pub fn initialize_slice_owned<T>(
+    slot: &mut [MaybeUninit<T>],
+    init: impl Init<[T]>,
+) -> OwningRef<'_, [T]> {
+    try_initialize_slice_owned(slot, init).unwrap_or_else(|e| match e {})
+}
+
 /// Initialize a `MaybeUninit<T>` and return a reference to the newly initialized slot.
 ///
 /// Code that receives the mutable reference returned by this function needs to keep in mind
@@ -579,3 +788,101 @@ pub fn initialize_pinned_owned<T>(
 ) -> Pin<OwningRef<'static, T>> {
     try_initialize_pinned_owned(slot, init).unwrap_or_else(|e| match e {})
 }
+
+/// Replace the value in `slot` with a newly initialized value, and return the old value.
+///
+/// If `init` fails or panics, the old value is put back into `slot`.
+pub fn try_reinit<T, Error>(slot: &mut T, init: impl Init<T, Error>) -> Result<T, Error> {
+    struct WriteBackOnDrop<T> {
+        slot: *mut T,
+        old: core::mem::ManuallyDrop<T>,
+    }
+    impl<T> Drop for WriteBackOnDrop<T> {
+        fn drop(&mut self) {
+            // SAFETY: `slot` is valid for writes, and `old` is not used after this
+            unsafe {
+                self.slot
+                    .write(core::mem::ManuallyDrop::take(&mut self.old))
+            };
+        }
+    }
+
+    let slot: *mut T = slot;
+    // SAFETY: `slot` is valid for reads, and `guard` writes the old value back unless initialization succeeds
+    let mut guard = WriteBackOnDrop {
+        slot,
+        old: core::mem::ManuallyDrop::new(unsafe { slot.read() }),
+    };
+    // SAFETY: `slot` is valid for writes, and its old value was moved out
+    unsafe { init.init(slot, ())? };
+    // SAFETY: `guard` is forgotten, so `old` is not used after this
+    let old = unsafe { core::mem::ManuallyDrop::take(&mut guard.old) };
+    core::mem::forget(guard);
+    Ok(old)
+}
+
+/// Replace the value in `slot` with a newly initialized value, and return the old value.
+///
+/// To avoid moving the old value out, use [`reinit_pinned`] (with [`Pin::new`] for `Unpin` types).
+pub fn reinit<T>(slot: &mut T, init: impl Init<T>) -> T {
+    try_reinit(slot, init).unwrap_or_else(|e| match e {})
+}
+
+/// Drop the value in `slot` in place, and initialize a new value in its place.
+///
+/// If `init` fails, `slot` is initialized with `fallback` instead, and the error is returned.
+///
+/// # Panics
+///
+/// Panics if `init`'s metadata does not match `slot`'s metadata.
+///
+/// Aborts if dropping the old value, `init`, or `fallback` panics, since `slot` would be left
+/// uninitialized.
+pub fn try_reinit_pinned<T: MetaSized, Error>(
+    slot: Pin<&mut T>,
+    init: impl PinInit<T, Error>,
+    fallback: impl PinInit<T>,
+) -> Result<(), Error> {
+    // SAFETY: the value is not moved out, only dropped in place and reinitialized
+    let slot: *mut T = unsafe { slot.get_unchecked_mut() };
+    assert!(
+        init.metadata() == core::ptr::metadata(slot)
+            && fallback.metadata() == core::ptr::metadata(slot),
+        "initializer metadata does not match the existing value"
+    );
+
+    let guard = util::AbortOnUnwind;
+    // SAFETY: `slot` is valid, and is reinitialized below (or the process aborts)
+    unsafe { slot.drop_in_place() };
+    // SAFETY: `slot` is valid for writes, and the metadata matches
+    let result = unsafe { init.init(slot, ()) };
+    if result.is_err() {
+        // SAFETY: `init` failed, so `slot` is uninitialized
+        unsafe { fallback.init(slot, ()).unwrap_or_else(|e| match e {}) };
+    }
+    core::mem::forget(guard);
+    result
+}
+
+/// Drop the value in `slot` in place, and initialize a new value in its place.
+///
+/// # Panics
+///
+/// Panics if `init`'s metadata does not match `slot`'s metadata.
+///
+/// Aborts if dropping the old value or `init` panics, since `slot` would be left uninitialized.
+pub fn reinit_pinned<T: MetaSized>(slot: Pin<&mut T>, init: impl PinInit<T>) {
+    // SAFETY: the value is not moved out, only dropped in place and reinitialized
+    let slot: *mut T = unsafe { slot.get_unchecked_mut() };
+    assert!(
+        init.metadata() == core::ptr::metadata(slot),
+        "initializer metadata does not match the existing value"
+    );
+
+    let guard = util::AbortOnUnwind;
+    // SAFETY: `slot` is valid, and is reinitialized below (or the process aborts)
+    unsafe { slot.drop_in_place() };
+    // SAFETY: `slot` is valid for writes, and the metadata matches
+    unsafe { init.init(slot, ()).unwrap_or_else(|e| match e {}) };
+    core::mem::forget(guard);
+}
@@ -0,0 +1,97 @@
+use core::{mem::MaybeUninit, pin::Pin};
+
+use crate::PinInit;
+
+/// A slot on the stack for [`stack_pin_init!`] and [`try_stack_pin_init!`],
+/// which drops its value (if initialized) when it goes out of scope.
+#[doc(hidden)]
+pub struct StackSlot<T> {
+    value: MaybeUninit<T>,
+    is_init: bool,
+}
+
+impl<T> StackSlot<T> {
+    pub fn uninit() -> Self {
+        Self {
+            value: MaybeUninit::uninit(),
+            is_init: false,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `self` must not be moved or forgotten after this is called, i.e. it must be a local
+    /// variable that is not accessible to user code (as declared by [`stack_pin_init!`]).
+    pub unsafe fn try_init<Error>(
+        &mut self,
+        init: impl PinInit<T, Error>,
+    ) -> Result<Pin<&mut T>, Error> {
+        assert!(!self.is_init, "stack slot was already initialized");
+        // SAFETY: `self.value` is uniquely borrowed, so it is valid for writes
+        unsafe { init.init(self.value.as_mut_ptr(), ())? };
+        self.is_init = true;
+        // SAFETY: we just initialized `self.value`, and the caller ensures that `self` will not be
+        // moved, and that it will be dropped (dropping the value) before its memory is reused.
+        Ok(unsafe { Pin::new_unchecked(self.value.assume_init_mut()) })
+    }
+
+    /// # Safety
+    ///
+    /// See [`StackSlot::try_init`].
+    pub unsafe fn init(&mut self, init: impl PinInit<T>) -> Pin<&mut T> {
+        // SAFETY: discharged to caller
+        unsafe { self.try_init(init) }.unwrap_or_else(|e| match e {})
+    }
+}
+
+impl<T> Drop for StackSlot<T> {
+    fn drop(&mut self) {
+        if self.is_init {
+            // SAFETY: the value is initialized, and not used after this
+            unsafe { self.value.assume_init_drop() };
+        }
+    }
+}
+
+/// Pin-initialize a value on the stack.
+///
+/// `stack_pin_init!(let x: Pin<&mut T> = init)` declares a hidden slot for a `T` in the current
+/// scope, initializes it with `init` (which must implement [`PinInit<T>`](PinInit)), and binds `x`
+/// to a pinned reference to it. The value is dropped when the scope ends, and cannot be moved or
+/// forgotten before then.
+///
+/// The type annotation can be omitted if it can be inferred.
+#[macro_export]
+macro_rules! stack_pin_init {
+    (let $var:ident $(: $t:ty)? = $init:expr $(;)?) => {
+        let init = $init;
+        let mut slot = $crate::__private::StackSlot::uninit();
+        // SAFETY: `slot` is a local that user code cannot name, so it is not moved or forgotten
+        #[allow(unused_mut)]
+        let mut $var $(: $t)? = unsafe { $crate::__private::StackSlot::init(&mut slot, init) };
+    };
+}
+
+/// Fallibly pin-initialize a value on the stack.
+///
+/// `try_stack_pin_init!(let x: Result<Pin<&mut T>, E> = init)` is like [`stack_pin_init!`],
+/// but `init` can fail, and `x` is bound to the result.
+///
+/// `try_stack_pin_init!(let x: Pin<&mut T> =? init)` propagates the error with `?` instead.
+#[macro_export]
+macro_rules! try_stack_pin_init {
+    (let $var:ident $(: $t:ty)? =? $init:expr $(;)?) => {
+        let init = $init;
+        let mut slot = $crate::__private::StackSlot::uninit();
+        // SAFETY: `slot` is a local that user code cannot name, so it is not moved or forgotten
+        #[allow(unused_mut)]
+        let mut $var $(: $t)? = unsafe { $crate::__private::StackSlot::try_init(&mut slot, init) }?;
+    };
+    (let $var:ident $(: $t:ty)? = $init:expr $(;)?) => {
+        let init = $init;
+        let mut slot = $crate::__private::StackSlot::uninit();
+        // SAFETY: `slot` is a local that user code cannot name, so it is not moved or forgotten
+        #[allow(unused_mut)]
+        let mut $var $(: $t)? = unsafe { $crate::__private::StackSlot::try_init(&mut slot, init) };
+    };
+}
@@ -0,0 +1,122 @@
+use core::{
+    cell::UnsafeCell,
+    fmt,
+    mem::MaybeUninit,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use noop_allocator::owning_ref::OwningRef;
+
+use crate::PinInit;
+
+/// A slot for a value in a `static`, which can be taken (and initialized) at most once.
+///
+/// This is a safe replacement for `unsafe { &mut *&raw mut STATIC }` when using
+/// [`initialize_pinned`](crate::initialize_pinned) and similar functions.
+pub struct StaticSlot<T> {
+    taken: AtomicBool,
+    slot: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: The slot is only ever accessed by the (single) caller that takes it,
+// which may be on a different thread than the one that created it.
+unsafe impl<T: Send> Sync for StaticSlot<T> {}
+
+/// The error returned when a [`StaticSlot`] has already been taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlreadyTaken;
+
+impl fmt::Display for AlreadyTaken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("static slot was already taken")
+    }
+}
+
+impl core::error::Error for AlreadyTaken {}
+
+/// Marks the slot as not taken again if initialization fails or panics.
+struct ReleaseOnDrop<'a, T>(&'a StaticSlot<T>);
+
+impl<T> Drop for ReleaseOnDrop<'_, T> {
+    fn drop(&mut self) {
+        self.0.taken.store(false, Ordering::Release);
+    }
+}
+
+impl<T> StaticSlot<T> {
+    pub const fn new() -> Self {
+        Self {
+            taken: AtomicBool::new(false),
+            slot: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Take the uninitialized slot, if it has not been taken before.
+    #[allow(clippy::mut_from_ref)]
+    pub fn take(&'static self) -> Result<&'static mut MaybeUninit<T>, AlreadyTaken> {
+        if self.taken.swap(true, Ordering::Acquire) {
+            return Err(AlreadyTaken);
+        }
+        // SAFETY: `taken` was not set, so this is the only reference to the slot that is handed out
+        Ok(unsafe { &mut *self.slot.get() })
+    }
+
+    /// Take the slot and initialize it, if it has not been taken before.
+    ///
+    /// If `init` fails or panics, the slot is not considered taken.
+    pub fn try_take_and_init<Error>(
+        &'static self,
+        init: impl PinInit<T, Error>,
+    ) -> Result<Result<Pin<&'static mut T>, Error>, AlreadyTaken> {
+        let slot = self.take()?;
+        let guard = ReleaseOnDrop(self);
+        let result = crate::try_initialize_pinned(slot, init);
+        if result.is_ok() {
+            core::mem::forget(guard);
+        }
+        Ok(result)
+    }
+
+    /// Take the slot and initialize it, if it has not been taken before.
+    pub fn take_and_init(
+        &'static self,
+        init: impl PinInit<T>,
+    ) -> Result<Pin<&'static mut T>, AlreadyTaken> {
+        self.try_take_and_init(init)
+            .map(|r| r.unwrap_or_else(|e| match e {}))
+    }
+
+    /// Take the slot and initialize it, if it has not been taken before,
+    /// and return an owning reference to the value.
+    ///
+    /// If `init` fails or panics, the slot is not considered taken.
+    pub fn try_take_and_init_owned<Error>(
+        &'static self,
+        init: impl PinInit<T, Error>,
+    ) -> Result<Result<Pin<OwningRef<'static, T>>, Error>, AlreadyTaken> {
+        let slot = self.take()?;
+        let guard = ReleaseOnDrop(self);
+        let result = crate::try_initialize_pinned_owned(slot, init);
+        if result.is_ok() {
+            core::mem::forget(guard);
+        }
+        Ok(result)
+    }
+
+    /// Take the slot and initialize it, if it has not been taken before,
+    /// and return an owning reference to the value.
+    pub fn take_and_init_owned(
+        &'static self,
+        init: impl PinInit<T>,
+    ) -> Result<Pin<OwningRef<'static, T>>, AlreadyTaken> {
+        self.try_take_and_init_owned(init)
+            .map(|r| r.unwrap_or_else(|e| match e {}))
+    }
+}
+
+impl<T> Default for StaticSlot<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
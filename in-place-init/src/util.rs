@@ -29,3 +29,44 @@ unsafe impl Length for RuntimeLength {
         self.length
     }
 }
+
+/// Aborts the process if dropped during unwinding (by panicking again).
+///
+/// Used when a panic would otherwise leave a live place uninitialized. Must be forgotten on success.
+pub(crate) struct AbortOnUnwind;
+
+impl Drop for AbortOnUnwind {
+    fn drop(&mut self) {
+        panic!("a panic occurred while a value was being reinitialized in place; aborting");
+    }
+}
+
+/// Drops the already-initialized prefix of a slice if initializing the rest of it fails or panics.
+pub(crate) struct Written<T> {
+    start: *mut T,
+    pub(crate) len: usize,
+}
+
+impl<T> Written<T> {
+    pub(crate) fn new(start: *mut T) -> Self {
+        Self { start, len: 0 }
+    }
+
+    /// The next `len` elements after the initialized prefix.
+    ///
+    /// # Safety
+    ///
+    /// The destination must have at least `self.len + len` elements.
+    pub(crate) unsafe fn next(&self, len: usize) -> *mut [T] {
+        core::ptr::slice_from_raw_parts_mut(unsafe { self.start.add(self.len) }, len)
+    }
+}
+
+impl<T> Drop for Written<T> {
+    fn drop(&mut self) {
+        // SAFETY: the first `self.len` elements are initialized
+        unsafe {
+            core::ptr::drop_in_place(core::ptr::slice_from_raw_parts_mut(self.start, self.len))
+        };
+    }
+}